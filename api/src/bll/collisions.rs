@@ -0,0 +1,309 @@
+use anyhow::{bail, Result};
+
+use crate::bll::state::SystemState;
+use crate::bo::celest_item::CelestItem;
+use crate::bo::collision_event::CollisionEvent;
+use crate::bo::epoch::Epoch;
+use crate::bo::simulation_options::CollisionMode;
+
+/// Détection des contacts entre objets et application du traitement demandé
+///
+/// Deux objets sont en contact lorsque la distance entre leurs centres est inférieure
+/// à la somme de leurs rayons en fin de pas. Les paires candidates sont obtenues par
+/// balayage des objets triés selon x, ce qui évite le test exhaustif des N² paires.
+/// Un pas trop grand devant la durée de la traversée peut laisser passer un contact.
+pub struct CollisionHandler {
+  mode: CollisionMode,
+  order: Vec<usize>,
+  pairs: Vec<(usize, usize)>,
+  pub events: Vec<CollisionEvent>,
+}
+
+impl CollisionHandler {
+  pub fn new(mode: CollisionMode) -> Self {
+    CollisionHandler { mode, order: Vec::new(), pairs: Vec::new(), events: Vec::new() }
+  }
+
+  /// Traite les contacts de l'état courant et indique si l'état a été modifié
+  ///
+  /// `items` doit correspondre à `state` indice par indice : un objet absorbé par une
+  /// fusion est retiré des deux.
+  pub fn resolve(&mut self, state: &mut SystemState, items: &mut Vec<CelestItem>, date: Epoch) -> Result<bool> {
+    if self.mode == CollisionMode::Ignore {
+      return Ok(false);
+    }
+
+    let mut changed = false;
+    loop {
+      self.detect(state);
+      let Some(&(i, j)) = self.pairs.first() else {
+        return Ok(changed);
+      };
+
+      match self.mode {
+        CollisionMode::Halt => {
+          let event = Self::event(state, items, i, j, self.mode, date);
+          bail!(
+            "collision entre {} et {} le {} à {:.0} m/s",
+            event.bodies[0],
+            event.bodies[1],
+            event.date,
+            event.relative_speed
+          );
+        }
+        CollisionMode::Merge => {
+          // Une fusion change les indices : on détecte à nouveau après chacune
+          self.merge(state, items, i, j, date);
+          changed = true;
+        }
+        CollisionMode::Bounce => {
+          for k in 0..self.pairs.len() {
+            let (i, j) = self.pairs[k];
+            changed |= self.bounce(state, items, i, j, date);
+          }
+          return Ok(changed);
+        }
+        CollisionMode::Ignore => return Ok(changed),
+      }
+    }
+  }
+
+  /// Balayage selon x : seules les paires dont l'écart en x est inférieur à la somme
+  /// des rayons sont examinées
+  fn detect(&mut self, state: &SystemState) {
+    self.pairs.clear();
+    let (xs, ys, zs) = state.positions.components();
+    let radii = &state.radii;
+    let max_radius = radii.iter().copied().fold(0.0, f64::max);
+
+    self.order.clear();
+    self.order.extend(0..state.len());
+    self.order.sort_unstable_by(|&a, &b| xs[a].total_cmp(&xs[b]));
+
+    for (k, &i) in self.order.iter().enumerate() {
+      for &j in &self.order[k + 1..] {
+        if xs[j] - xs[i] > radii[i] + max_radius {
+          break;
+        }
+        // Les particules test ne se rencontrent pas entre elles
+        if state.masses[i] == 0.0 && state.masses[j] == 0.0 {
+          continue;
+        }
+        let reach = radii[i] + radii[j];
+        let d_squared = (xs[j] - xs[i]).powi(2) + (ys[j] - ys[i]).powi(2) + (zs[j] - zs[i]).powi(2);
+        if d_squared < reach * reach {
+          self.pairs.push((i.min(j), i.max(j)));
+        }
+      }
+    }
+    self.pairs.sort_unstable();
+  }
+
+  fn event(
+    state: &SystemState,
+    items: &[CelestItem],
+    i: usize,
+    j: usize,
+    outcome: CollisionMode,
+    date: Epoch,
+  ) -> CollisionEvent {
+    let (pi, pj) = (state.positions.get(i), state.positions.get(j));
+    let (vi, vj) = (state.velocities.get(i), state.velocities.get(j));
+    let relative_speed = (0..3).map(|k| (vi[k] - vj[k]).powi(2)).sum::<f64>().sqrt();
+    // Point de contact sur la ligne des centres, au prorata des rayons
+    let share = state.radii[i] / (state.radii[i] + state.radii[j]);
+    CollisionEvent {
+      date,
+      bodies: [items[i].name.clone(), items[j].name.clone()],
+      outcome,
+      relative_speed,
+      position: std::array::from_fn(|k| pi[k] + share * (pj[k] - pi[k])),
+    }
+  }
+
+  /// Fusion parfaitement inélastique : le plus massif (ou l'objet fixe) absorbe l'autre
+  ///
+  /// La masse, la quantité de mouvement et le volume sont conservés. Un objet fixe
+  /// garde sa position et reste immobile.
+  fn merge(&mut self, state: &mut SystemState, items: &mut Vec<CelestItem>, i: usize, j: usize, date: Epoch) {
+    let keeps_i = match (state.is_fixed_index(i), state.is_fixed_index(j)) {
+      (true, _) => true,
+      (false, true) => false,
+      _ => state.masses[i] >= state.masses[j],
+    };
+    let (survivor, absorbed) = if keeps_i { (i, j) } else { (j, i) };
+
+    let event = Self::event(state, items, survivor, absorbed, CollisionMode::Merge, date);
+    let (ms, ma) = (state.masses[survivor], state.masses[absorbed]);
+    let mass = ms + ma;
+    if !state.is_fixed_index(survivor) && mass > 0.0 {
+      let (ps, pa) = (state.positions.get(survivor), state.positions.get(absorbed));
+      let (vs, va) = (state.velocities.get(survivor), state.velocities.get(absorbed));
+      state.positions.set(survivor, std::array::from_fn(|k| (ms * ps[k] + ma * pa[k]) / mass));
+      state.velocities.set(survivor, std::array::from_fn(|k| (ms * vs[k] + ma * va[k]) / mass));
+    }
+    state.masses[survivor] = mass;
+    state.radii[survivor] = (state.radii[survivor].powi(3) + state.radii[absorbed].powi(3)).cbrt();
+
+    state.remove(absorbed);
+    items.remove(absorbed);
+    tracing::info!("💥 {} absorbe {} le {}", event.bodies[0], event.bodies[1], event.date);
+    self.events.push(event);
+  }
+
+  /// Rebond élastique : inversion de la composante normale de la vitesse relative,
+  /// un objet fixe se comportant comme une masse infinie
+  fn bounce(&mut self, state: &mut SystemState, items: &[CelestItem], i: usize, j: usize, date: Epoch) -> bool {
+    let (pi, pj) = (state.positions.get(i), state.positions.get(j));
+    let (vi, vj) = (state.velocities.get(i), state.velocities.get(j));
+    let d: [f64; 3] = std::array::from_fn(|k| pi[k] - pj[k]);
+    let distance = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+    if distance == 0.0 {
+      return false;
+    }
+    let normal = d.map(|x| x / distance);
+    let approach: f64 = (0..3).map(|k| (vi[k] - vj[k]) * normal[k]).sum();
+    // Objets déjà en train de s'éloigner : le rebond a eu lieu au pas précédent
+    if approach >= 0.0 {
+      return false;
+    }
+
+    let (mi, mj) = (state.masses[i], state.masses[j]);
+    let (wi, wj) = match (state.is_fixed_index(i), state.is_fixed_index(j)) {
+      (true, true) => return false,
+      (true, false) => (0.0, 2.0),
+      (false, true) => (2.0, 0.0),
+      _ => (2.0 * mj / (mi + mj), 2.0 * mi / (mi + mj)),
+    };
+    self.events.push(Self::event(state, items, i, j, CollisionMode::Bounce, date));
+    state.velocities.set(i, std::array::from_fn(|k| vi[k] - wi * approach * normal[k]));
+    state.velocities.set(j, std::array::from_fn(|k| vj[k] + wj * approach * normal[k]));
+    true
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn body(name: &str, mass: f64, radius: f64, position: [f64; 3], velocity: [f64; 3]) -> CelestItem {
+    CelestItem { name: name.into(), mass, radius, position, velocity, ..Default::default() }
+  }
+
+  fn momentum(state: &SystemState) -> [f64; 3] {
+    std::array::from_fn(|k| (0..state.len()).map(|i| state.masses[i] * state.velocities.get(i)[k]).sum())
+  }
+
+  fn kinetic_energy(state: &SystemState) -> f64 {
+    (0..state.len()).map(|i| 0.5 * state.masses[i] * state.velocities.get(i).iter().map(|v| v * v).sum::<f64>()).sum()
+  }
+
+  fn resolve(mode: CollisionMode, items: &mut Vec<CelestItem>) -> (CollisionHandler, SystemState, Result<bool>) {
+    let mut state = SystemState::from_items(items);
+    let mut handler = CollisionHandler::new(mode);
+    let changed = handler.resolve(&mut state, items, Epoch::from_nanoseconds(0));
+    (handler, state, changed)
+  }
+
+  fn overlapping() -> Vec<CelestItem> {
+    vec![
+      body("A", 3e20, 2e5, [0.0, 0.0, 0.0], [1e3, 0.0, 0.0]),
+      body("B", 1e20, 1e5, [2.5e5, 1e4, 0.0], [-2e3, 500.0, 0.0]),
+      body("C", 1e20, 1e5, [1e9, 0.0, 0.0], [0.0, 1e3, 0.0]),
+    ]
+  }
+
+  #[test]
+  fn merge_conserves_mass_momentum_and_volume() {
+    let mut items = overlapping();
+    let before = SystemState::from_items(&items);
+    let (handler, state, changed) = resolve(CollisionMode::Merge, &mut items);
+
+    assert!(changed.unwrap());
+    assert_eq!(items.iter().map(|item| item.name.as_str()).collect::<Vec<_>>(), ["A", "C"]);
+    assert_eq!(handler.events.len(), 1);
+    assert_eq!(handler.events[0].bodies, ["A".to_string(), "B".to_string()]);
+    assert_eq!(state.masses.iter().sum::<f64>(), before.masses.iter().sum::<f64>());
+    let (p0, p1) = (momentum(&before), momentum(&state));
+    assert!((0..3).all(|k| (p1[k] - p0[k]).abs() < 1e-12 * 1e24), "{p0:?} {p1:?}");
+    let volume = |state: &SystemState| state.radii.iter().map(|r| r.powi(3)).sum::<f64>();
+    assert!((volume(&state) / volume(&before) - 1.0).abs() < 1e-12);
+    // Le survivant est placé au centre de masse de la paire
+    assert!((state.positions.get(0)[0] - 0.25 * 2.5e5).abs() < 1e-6);
+  }
+
+  #[test]
+  fn bounce_conserves_kinetic_energy_and_momentum() {
+    let mut items = overlapping();
+    let before = SystemState::from_items(&items);
+    let (handler, state, changed) = resolve(CollisionMode::Bounce, &mut items);
+
+    assert!(changed.unwrap());
+    assert_eq!((items.len(), handler.events.len()), (3, 1));
+    assert!((kinetic_energy(&state) / kinetic_energy(&before) - 1.0).abs() < 1e-12);
+    let (p0, p1) = (momentum(&before), momentum(&state));
+    assert!((0..3).all(|k| (p1[k] - p0[k]).abs() < 1e-12 * 1e24), "{p0:?} {p1:?}");
+    // Les objets s'éloignent désormais : le pas suivant ne rebondit pas une seconde fois
+    let mut again = state.clone();
+    assert!(!CollisionHandler::new(CollisionMode::Bounce).resolve(&mut again, &mut items, Epoch::from_nanoseconds(0)).unwrap());
+
+    // Contre un objet fixe, la vitesse normale de l'autre est simplement renversée
+    let mut items = vec![
+      CelestItem { fixed: true, ..body("Mur", 1e30, 1e6, [0.0; 3], [0.0; 3]) },
+      body("Balle", 1.0, 1.0, [1e6, 0.0, 0.0], [-10.0, 3.0, 0.0]),
+    ];
+    let (_, state, _) = resolve(CollisionMode::Bounce, &mut items);
+    assert_eq!((state.velocities.get(0), state.velocities.get(1)), ([0.0; 3], [10.0, 3.0, 0.0]));
+  }
+
+  #[test]
+  fn halt_and_ignore_leave_the_state_untouched() {
+    let mut items = overlapping();
+    let (_, _, result) = resolve(CollisionMode::Halt, &mut items);
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("collision entre A et B"), "{error}");
+
+    let (handler, state, changed) = resolve(CollisionMode::Ignore, &mut items);
+    assert!(!changed.unwrap() && handler.events.is_empty());
+    assert_eq!((items.len(), state.velocities.get(1)), (3, [-2e3, 500.0, 0.0]));
+    assert_eq!(CollisionMode::default(), CollisionMode::Ignore);
+  }
+
+  #[test]
+  fn sweep_finds_the_same_pairs_as_the_exhaustive_test() {
+    // Nuage pseudo-aléatoire dense, avec quelques particules test qui ne se rencontrent pas entre elles
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+      seed ^= seed << 13;
+      seed ^= seed >> 7;
+      seed ^= seed << 17;
+      (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let items: Vec<CelestItem> = (0..300)
+      .map(|i| {
+        let mass = if i % 5 == 0 { 0.0 } else { 1e10 };
+        let position = [next() * 1e4, next() * 1e4, next() * 1e4];
+        body(&i.to_string(), mass, 50.0 + next() * 400.0, position, [0.0; 3])
+      })
+      .collect();
+    let state = SystemState::from_items(&items);
+
+    let mut expected = Vec::new();
+    for i in 0..items.len() {
+      for j in i + 1..items.len() {
+        let (pi, pj) = (state.positions.get(i), state.positions.get(j));
+        let distance = (0..3).map(|k| (pi[k] - pj[k]).powi(2)).sum::<f64>().sqrt();
+        let massive = state.masses[i] > 0.0 || state.masses[j] > 0.0;
+        if massive && distance < state.radii[i] + state.radii[j] {
+          expected.push((i, j));
+        }
+      }
+    }
+
+    let mut handler = CollisionHandler::new(CollisionMode::Bounce);
+    handler.detect(&state);
+    assert!(expected.len() > 10, "{}", expected.len());
+    assert_eq!(handler.pairs, expected);
+  }
+}
@@ -0,0 +1,118 @@
+use crate::bll::gravity::{G, MIN_DISTANCE};
+use crate::bo::celest_item::CelestItem;
+use crate::bo::diagnostics::{ConservedQuantities, Diagnostics};
+
+fn norm(v: [f64; 3]) -> f64 {
+  (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+fn difference(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+  [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Erreur rapportée à son échelle, laissée absolue si l'échelle est nulle
+fn relative(error: f64, scale: f64) -> f64 {
+  if scale > 0.0 { error / scale } else { error }
+}
+
+/// Énergie, quantité de mouvement, moment cinétique et centre de masse du système
+pub fn conserved_quantities(state: &[CelestItem]) -> ConservedQuantities {
+  let mut energy = 0.0;
+  let mut linear_momentum = [0.0; 3];
+  let mut angular_momentum = [0.0; 3];
+  let mut weighted_position = [0.0; 3];
+  let mut total_mass = 0.0;
+
+  for (i, item) in state.iter().enumerate() {
+    let (p, v, m) = (item.position, item.velocity, item.mass);
+    energy += 0.5 * m * (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]);
+    for other in &state[i + 1..] {
+      // Même coupure à courte distance que le calcul des forces
+      let r = norm(difference(other.position, p));
+      if r >= MIN_DISTANCE {
+        energy -= G * m * other.mass / r;
+      }
+    }
+
+    for k in 0..3 {
+      linear_momentum[k] += m * v[k];
+      weighted_position[k] += m * p[k];
+    }
+    angular_momentum[0] += m * (p[1] * v[2] - p[2] * v[1]);
+    angular_momentum[1] += m * (p[2] * v[0] - p[0] * v[2]);
+    angular_momentum[2] += m * (p[0] * v[1] - p[1] * v[0]);
+    total_mass += m;
+  }
+
+  let center_of_mass = if total_mass > 0.0 {
+    weighted_position.map(|x| x / total_mass)
+  } else {
+    [0.0; 3]
+  };
+
+  ConservedQuantities { energy, linear_momentum, angular_momentum, center_of_mass }
+}
+
+/// Compare les grandeurs conservées entre l'état initial et l'état final après `elapsed` secondes
+pub fn diagnose(initial: &[CelestItem], end: &[CelestItem], elapsed: f64) -> Diagnostics {
+  let start = conserved_quantities(initial);
+  let finish = conserved_quantities(end);
+
+  let total_mass: f64 = initial.iter().map(|item| item.mass).sum();
+  let momentum_scale: f64 = initial.iter().map(|item| item.mass * norm(item.velocity)).sum();
+  let size = initial
+    .iter()
+    .map(|item| norm(difference(item.position, start.center_of_mass)))
+    .fold(0.0, f64::max);
+
+  // Le centre de masse doit suivre un mouvement rectiligne uniforme
+  let expected_center = if total_mass > 0.0 {
+    std::array::from_fn(|k| start.center_of_mass[k] + start.linear_momentum[k] / total_mass * elapsed)
+  } else {
+    start.center_of_mass
+  };
+
+  Diagnostics {
+    start,
+    end: finish,
+    energy_error: relative((finish.energy - start.energy).abs(), start.energy.abs()),
+    linear_momentum_error: relative(norm(difference(finish.linear_momentum, start.linear_momentum)), momentum_scale),
+    angular_momentum_error: relative(
+      norm(difference(finish.angular_momentum, start.angular_momentum)),
+      norm(start.angular_momentum),
+    ),
+    center_of_mass_error: relative(norm(difference(finish.center_of_mass, expected_center)), size),
+    timestamp: end.first().and_then(|item| item.timestamp),
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bll::integrator::fixtures::*;
+  use crate::bo::simulation_options::{IntegratorKind, SimulationOptions};
+
+  #[test]
+  fn exact_two_body_motion_has_no_error() {
+    // Orbite excentrique dont le centre de masse dérive à 1 km/s
+    let mut initial = orbit(0.3);
+    for item in &mut initial {
+      item.velocity[0] += 1e3;
+    }
+    let elapsed = 2.7 * period();
+    let kepler = SimulationOptions { integrator: IntegratorKind::Kepler, ..Default::default() };
+    let (end, _, _) = integrate(&kepler, &initial, elapsed, elapsed);
+
+    let report = diagnose(&initial, &end, elapsed);
+    for error in [report.energy_error, report.linear_momentum_error, report.angular_momentum_error, report.center_of_mass_error] {
+      assert!(error < 1e-12, "{report:?}");
+    }
+
+    // Une vitesse faussée de 0,1 % se lit dans chaque bilan
+    let mut wrong = end.clone();
+    wrong[1].velocity = wrong[1].velocity.map(|v| 1.001 * v);
+    let report = diagnose(&initial, &wrong, elapsed);
+    assert!(report.energy_error > 1e-6 && report.linear_momentum_error > 1e-8 && report.angular_momentum_error > 1e-4, "{report:?}");
+  }
+}
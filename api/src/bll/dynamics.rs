@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use crate::bll::forces::radiation_pressure::RadiationPressure;
+use crate::bll::forces::thrust::ConstantThrust;
+use crate::bll::forces::yarkovsky::Yarkovsky;
+use crate::bll::forces::Force;
+use crate::bll::gravity::{self, relativity::PostNewtonian, ForceSolver};
+use crate::bll::state::Vectors;
+use crate::bo::force_parameters::ForceParameters;
+use crate::bo::simulation_options::SimulationOptions;
+
+/// Modèle d'accélération évalué par les intégrateurs : gravitation et forces additionnelles
+pub struct Dynamics {
+  solver: Arc<dyn ForceSolver>,
+  forces: Vec<Box<dyn Force>>,
+}
+
+impl Dynamics {
+  /// Gravitation seule, calculée par `solver`
+  pub fn new(solver: Arc<dyn ForceSolver>) -> Self {
+    Dynamics { solver, forces: Vec::new() }
+  }
+
+  /// Ajoute une force au modèle
+  pub fn with(mut self, force: impl Force + 'static) -> Self {
+    self.forces.push(Box::new(force));
+    self
+  }
+
+  /// Instancie le modèle choisi par la requête, avec les seules forces sollicitées par les paramètres des corps
+  pub fn build(options: &SimulationOptions, parameters: &[ForceParameters]) -> Self {
+    let mut dynamics = Self::new(gravity::build(options));
+    if options.post_newtonian {
+      dynamics = dynamics.with(PostNewtonian);
+    }
+    if parameters.iter().any(|body| body.area_to_mass != 0.0) {
+      dynamics = dynamics.with(RadiationPressure);
+    }
+    if parameters.iter().any(|body| body.yarkovsky != 0.0) {
+      dynamics = dynamics.with(Yarkovsky);
+    }
+    if parameters.iter().any(|body| body.thrust.is_some()) {
+      dynamics = dynamics.with(ConstantThrust);
+    }
+    dynamics
+  }
+
+  /// Indique si les accélérations dépendent des vitesses, et pas seulement des positions
+  pub fn depends_on_velocity(&self) -> bool {
+    self.forces.iter().any(|force| force.depends_on_velocity())
+  }
+
+  /// Écrit dans `out` l'accélération de chaque corps pour les positions et vitesses données
+  pub fn accelerations(
+    &self,
+    positions: &Vectors,
+    velocities: &Vectors,
+    masses: &[f64],
+    parameters: &[ForceParameters],
+    out: &mut Vectors,
+  ) {
+    // Les corps sans masse, particules test rangées en fin d'état, ne sont pas sources
+    let sources = masses.iter().rposition(|&m| m != 0.0).map_or(0, |last| last + 1);
+    self.solver.accelerations(positions, &masses[..sources], out);
+    for force in &self.forces {
+      force.add_accelerations(positions, velocities, masses, parameters, out);
+    }
+  }
+}
@@ -0,0 +1,102 @@
+pub mod vsop87;
+// Les phases des tables valent souvent π, arrondi à la précision de Meeus
+#[allow(clippy::approx_constant)]
+mod vsop87_data;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use crate::bo::celest_item::CelestItem;
+use crate::bo::ephemeris::PositionDifference;
+use crate::bo::epoch::Epoch;
+use crate::bo::simulation_options::SimulationOptions;
+use crate::bo::simulation_result::SimulationResult;
+
+/// Interface commune des sources de positions : intégration numérique ou théorie analytique
+#[async_trait]
+pub trait Ephemeris: Send + Sync {
+  /// État des objets célestes à la date `date` ; une théorie analytique ignore les options d'intégration
+  async fn states_at(&self, date: Epoch, options: &SimulationOptions) -> Result<SimulationResult>;
+}
+
+/// Écart de position de chaque corps présent dans les deux solutions
+///
+/// Les positions sont rapportées au Soleil de chaque solution, ce qui retire de l'écart
+/// la différence de barycentre entre les deux ensembles de corps. Les noms sont
+/// rapprochés par leurs alias, un export Horizons nommant les corps en anglais.
+pub fn compare(numerical: &[CelestItem], analytic: &[CelestItem]) -> Result<Vec<PositionDifference>> {
+  let sun = |items: &[CelestItem]| items.iter().find(|item| vsop87::is_sun(&item.name)).map(|item| item.position);
+  let numerical_sun = sun(numerical).context("le Soleil est absent de la solution numérique")?;
+  let analytic_sun = sun(analytic).context("le Soleil est absent de la solution analytique")?;
+
+  let differences = numerical
+    .iter()
+    .filter(|item| !vsop87::is_sun(&item.name))
+    .filter_map(|item| {
+      let name = vsop87::canonical_name(&item.name);
+      let other = analytic.iter().find(|other| vsop87::canonical_name(&other.name) == name)?;
+      let numerical: [f64; 3] = std::array::from_fn(|k| item.position[k] - numerical_sun[k]);
+      let analytic: [f64; 3] = std::array::from_fn(|k| other.position[k] - analytic_sun[k]);
+      let difference: [f64; 3] = std::array::from_fn(|k| numerical[k] - analytic[k]);
+      Some(PositionDifference {
+        name: item.name.clone(),
+        timestamp: item.timestamp,
+        numerical,
+        analytic,
+        difference,
+        distance: norm(difference),
+        angular_separation: norm(cross(numerical, analytic)).atan2(dot(numerical, analytic)).to_degrees() * 3600.0,
+      })
+    })
+    .collect();
+  Ok(differences)
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+  [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+  dot(a, a).sqrt()
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bll::time;
+  use crate::bo::time_scale::TimeScale;
+
+  #[test]
+  fn differences_are_heliocentric_and_matched_by_alias() {
+    let date = time::parse("2024-03-20T00:00:00Z", TimeScale::Utc).unwrap();
+    let analytic = vsop87::Vsop87::celest_items(date).unwrap();
+
+    // Même solution décalée en bloc, avec Mars déplacé de 1 000 km et des noms anglais
+    let mut numerical = analytic.clone();
+    for item in numerical.iter_mut() {
+      item.position[0] += 1e9;
+      item.name = match item.name.as_str() {
+        "Soleil" => "Sun".into(),
+        "Terre" => "Earth".into(),
+        name => name.into(),
+      };
+    }
+    numerical.iter_mut().find(|item| item.name == "Mars").unwrap().position[2] += 1e6;
+    numerical.retain(|item| item.name != "Neptune");
+
+    let differences = compare(&numerical, &analytic).unwrap();
+    assert_eq!(differences.len(), 7);
+    for difference in &differences {
+      let expected = if difference.name == "Mars" { 1e6 } else { 0.0 };
+      assert!((difference.distance - expected).abs() < 1e-3, "{difference:?}");
+    }
+    let mars = differences.iter().find(|difference| difference.name == "Mars").unwrap();
+    assert!(mars.angular_separation > 0.5 && mars.angular_separation < 1.5, "{}", mars.angular_separation);
+    assert!(differences.iter().any(|difference| difference.name == "Earth"));
+  }
+}
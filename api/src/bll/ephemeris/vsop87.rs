@@ -0,0 +1,228 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+
+use std::f64::consts::PI;
+
+use crate::bll::ephemeris::vsop87_data::{self, Series};
+use crate::bll::ephemeris::Ephemeris;
+use crate::bll::time;
+use crate::bo::celest_item::CelestItem;
+use crate::bo::epoch::Epoch;
+use crate::bo::simulation_options::SimulationOptions;
+use crate::bo::simulation_result::SimulationResult;
+use crate::bo::time_scale::TimeScale;
+
+const ASTRONOMICAL_UNIT: f64 = 1.495_978_707e11;
+const ARCSECOND: f64 = PI / 648_000.0;
+const DAYS_PER_MILLENNIUM: f64 = 365_250.0;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+// Au-delà de ±4 000 ans autour de J2000, les séries tronquées divergent
+const VALIDITY_MILLENNIA: f64 = 4.0;
+// Demi-intervalle des différences finies centrées donnant les vitesses (s)
+const VELOCITY_STEP: f64 = 3600.0;
+
+/// Corps décrit par la théorie, sous son nom français suivi de ses alias
+struct Body {
+  names: &'static [&'static str],
+  mass: f64,
+  radius: f64,
+}
+
+const SUN: Body = Body { names: &["Soleil", "Sun"], mass: 1.9885e30, radius: 6.9634e8 };
+
+const PLANETS: [(Body, [Series; 3]); 8] = [
+  (Body { names: &["Mercure", "Mercury"], mass: 3.3011e23, radius: 2.4397e6 }, vsop87_data::MERCURY),
+  (Body { names: &["Venus", "Vénus"], mass: 4.8675e24, radius: 6.0518e6 }, vsop87_data::VENUS),
+  (Body { names: &["Terre", "Earth"], mass: 5.97237e24, radius: 6.371e6 }, vsop87_data::EARTH),
+  (Body { names: &["Mars"], mass: 6.4171e23, radius: 3.3895e6 }, vsop87_data::MARS),
+  (Body { names: &["Jupiter"], mass: 1.8982e27, radius: 6.9911e7 }, vsop87_data::JUPITER),
+  (Body { names: &["Saturne", "Saturn"], mass: 5.6834e26, radius: 5.8232e7 }, vsop87_data::SATURN),
+  (Body { names: &["Uranus"], mass: 8.6810e25, radius: 2.5362e7 }, vsop87_data::URANUS),
+  (Body { names: &["Neptune"], mass: 1.02413e26, radius: 2.4622e7 }, vsop87_data::NEPTUNE),
+];
+
+/// Nom français d'un corps de la théorie, quel que soit l'alias employé (« Earth », « Sun »…)
+pub fn canonical_name(name: &str) -> &str {
+  std::iter::once(&SUN)
+    .chain(PLANETS.iter().map(|(body, _)| body))
+    .find(|body| body.names.iter().any(|alias| alias.eq_ignore_ascii_case(name)))
+    .map_or(name, |body| body.names[0])
+}
+
+pub fn is_sun(name: &str) -> bool {
+  canonical_name(name) == SUN.names[0]
+}
+
+/// Théorie planétaire analytique VSOP87 (Bretagnon et Francou, 1988), version D tronquée
+///
+/// Les séries donnent les coordonnées héliocentriques rapportées à l'écliptique et à
+/// l'équinoxe de la date, ramenées ici à l'écliptique J2000 de la simulation par la
+/// précession de Lieske. Le Soleil est placé de sorte que le barycentre des neuf corps
+/// soit à l'origine, et les vitesses sont obtenues par différences finies. La Terre
+/// de VSOP87 est le centre de la Terre, et non le barycentre Terre-Lune. Les séries sont
+/// évaluées en temps dynamique barycentrique (TDB).
+pub struct Vsop87;
+
+impl Vsop87 {
+  /// Soleil et planètes à la date `date`, en coordonnées barycentriques
+  pub fn celest_items(date: Epoch) -> Result<Vec<CelestItem>> {
+    let days = time::julian_day(date, TimeScale::Tdb) - time::J2000_JULIAN_DAY;
+    if (days / DAYS_PER_MILLENNIUM).abs() > VALIDITY_MILLENNIA {
+      bail!("VSOP87 n'est valable qu'à moins de {VALIDITY_MILLENNIA} millénaires de J2000");
+    }
+
+    let step = VELOCITY_STEP / SECONDS_PER_DAY;
+    let (before, now, after) = (
+      Self::barycentric(days - step),
+      Self::barycentric(days),
+      Self::barycentric(days + step),
+    );
+    let bodies = std::iter::once(&SUN).chain(PLANETS.iter().map(|(body, _)| body));
+    Ok(
+      bodies
+        .enumerate()
+        .map(|(i, body)| CelestItem {
+          name: body.names[0].into(),
+          mass: body.mass,
+          radius: body.radius,
+          position: now[i],
+          velocity: std::array::from_fn(|k| (after[i][k] - before[i][k]) / (2.0 * VELOCITY_STEP)),
+          timestamp: Some(date),
+          fixed: false,
+          test_particle: false,
+          forces: None,
+          spacecraft: None,
+          orbit: None,
+        })
+        .collect(),
+    )
+  }
+
+  /// Positions barycentriques du Soleil puis des planètes, `days` jours TDB après J2000
+  fn barycentric(days: f64) -> Vec<[f64; 3]> {
+    let tau = days / DAYS_PER_MILLENNIUM;
+    let mut positions = vec![[0.0; 3]];
+    positions.extend(PLANETS.iter().map(|(_, series)| heliocentric(series, tau)));
+
+    let total_mass = SUN.mass + PLANETS.iter().map(|(body, _)| body.mass).sum::<f64>();
+    let center: [f64; 3] = std::array::from_fn(|k| {
+      PLANETS.iter().zip(&positions[1..]).map(|((body, _), position)| body.mass * position[k]).sum::<f64>() / total_mass
+    });
+    for position in positions.iter_mut() {
+      for k in 0..3 {
+        position[k] -= center[k];
+      }
+    }
+    positions
+  }
+}
+
+#[async_trait]
+impl Ephemeris for Vsop87 {
+  async fn states_at(&self, date: Epoch, _options: &SimulationOptions) -> Result<SimulationResult> {
+    let celest_items = Self::celest_items(date)?;
+    Ok(SimulationResult {
+      celest_items,
+      test_particles: vec![],
+      stats: None,
+      diagnostics: None,
+      collisions: vec![],
+      events: vec![],
+      orbital_elements: vec![],
+    })
+  }
+}
+
+/// Somme Σ τⁿ Σ A cos(B + C τ) des séries d'une coordonnée
+fn evaluate(series: Series, tau: f64) -> f64 {
+  let sum = series.iter().rev().fold(0.0, |acc, terms| {
+    acc * tau + terms.iter().map(|[a, b, c]| a * (b + c * tau).cos()).sum::<f64>()
+  });
+  sum * 1e-8
+}
+
+/// Longitude, latitude (rad) et distance (ua) héliocentriques, dans l'écliptique de la date
+fn spherical(series: &[Series; 3], tau: f64) -> [f64; 3] {
+  series.map(|coordinate| evaluate(coordinate, tau))
+}
+
+/// Position héliocentrique en m dans l'écliptique J2000, `tau` millénaires juliens après J2000
+fn heliocentric(series: &[Series; 3], tau: f64) -> [f64; 3] {
+  let [longitude, latitude, distance] = spherical(series, tau);
+  let r = distance * ASTRONOMICAL_UNIT;
+  let of_date = [
+    r * latitude.cos() * longitude.cos(),
+    r * latitude.cos() * longitude.sin(),
+    r * latitude.sin(),
+  ];
+  to_ecliptic_j2000(of_date, tau * 10.0)
+}
+
+/// Ramène un vecteur de l'écliptique et de l'équinoxe de la date à ceux de J2000
+///
+/// `t` est compté en siècles juliens ; angles de précession de Lieske (Meeus, 21.5).
+fn to_ecliptic_j2000(v: [f64; 3], t: f64) -> [f64; 3] {
+  let eta = (47.0029 * t - 0.03302 * t * t + 0.000060 * t * t * t) * ARCSECOND;
+  let pi = 174.876384_f64.to_radians() + (-869.8089 * t + 0.03536 * t * t) * ARCSECOND;
+  let p = (5029.0966 * t + 1.11113 * t * t - 0.000006 * t * t * t) * ARCSECOND;
+
+  // Longitudes comptées depuis le nœud des deux écliptiques, puis rotation de l'inclinaison η
+  let [x, y, z] = v;
+  let (s, c) = (pi + p).sin_cos();
+  let (x, y) = (c * x + s * y, -s * x + c * y);
+  let (s, c) = eta.sin_cos();
+  let (y, z) = (c * y - s * z, s * y + c * z);
+  let (s, c) = pi.sin_cos();
+  [c * x - s * y, s * x + c * y, z]
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn series_match_meeus_worked_examples() {
+    // Vénus le 20 décembre 1992 à 0 h TD (exemple 32.a) et la Terre le 13 octobre 1992 (exemple 25.b)
+    let cases = [
+      (vsop87_data::VENUS, 2_448_976.5, [26.11428, -2.62070, 0.724603]),
+      (vsop87_data::EARTH, 2_448_908.5, [19.907372, -0.000179, 0.99760775]),
+    ];
+    for (series, julian_day, [longitude, latitude, distance]) in cases {
+      let [l, b, r] = spherical(&series, (julian_day - time::J2000_JULIAN_DAY) / DAYS_PER_MILLENNIUM);
+      assert!((l.to_degrees().rem_euclid(360.0) - longitude).abs() < 1e-5, "{}", l.to_degrees());
+      assert!((b.to_degrees() - latitude).abs() < 1e-5, "{}", b.to_degrees());
+      assert!((r - distance).abs() < 1e-6, "{r}");
+    }
+
+    // À J2000, la précession est nulle et le barycentre à l'origine
+    let items = Vsop87::celest_items(time::from_julian_day(time::J2000_JULIAN_DAY, TimeScale::Tdb).unwrap()).unwrap();
+    let total_mass: f64 = items.iter().map(|item| item.mass).sum();
+    let center: [f64; 3] = std::array::from_fn(|k| items.iter().map(|item| item.mass * item.position[k]).sum::<f64>());
+    assert!(center.iter().all(|x| (x / total_mass).abs() < 1e-3), "{center:?}");
+    let earth = items.iter().find(|item| item.name == "Terre").unwrap();
+    let speed = earth.velocity.iter().map(|v| v * v).sum::<f64>().sqrt();
+    assert!((speed - 30_290.0).abs() < 100.0, "{speed}");
+  }
+
+  #[test]
+  fn precession_reduces_meeus_example_to_j2000() {
+    let spherical_to_cartesian = |longitude: f64, latitude: f64| {
+      let (l, b) = (longitude.to_radians(), latitude.to_radians());
+      [b.cos() * l.cos(), b.cos() * l.sin(), b.sin()]
+    };
+    let longitude_latitude = |[x, y, z]: [f64; 3]| (y.atan2(x).to_degrees().rem_euclid(360.0), z.asin().to_degrees());
+
+    // Exemple 21.c : Vénus en λ0 = 149,48194°, β0 = 1,76549° (J2000) se trouve en
+    // λ = 118,704°, β = 1,615° dans l'écliptique de date du -214 juin 30,0 (JDE 1643074,5)
+    let t = (1_643_074.5 - time::J2000_JULIAN_DAY) / 36_525.0;
+    let (longitude, latitude) = longitude_latitude(to_ecliptic_j2000(spherical_to_cartesian(118.704, 1.615), t));
+    assert!((longitude - 149.48194).abs() < 1e-3, "{longitude}");
+    assert!((latitude - 1.76549).abs() < 1e-3, "{latitude}");
+
+    // Un siècle après J2000, l'équinoxe de date a reculé de p ≈ 5029,1″ sur l'écliptique J2000
+    let (longitude, latitude) = longitude_latitude(to_ecliptic_j2000([1.0, 0.0, 0.0], 1.0));
+    assert!((longitude - (360.0 - 5029.0966 / 3600.0)).abs() < 2e-3, "{longitude}");
+    assert!(latitude.abs() < 0.02, "{latitude}");
+  }
+}
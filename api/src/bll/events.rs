@@ -0,0 +1,303 @@
+use anyhow::{bail, Result};
+
+use crate::bll::forces::dominant;
+use crate::bll::state::SystemState;
+use crate::bo::celest_item::CelestItem;
+use crate::bo::epoch::Epoch;
+use crate::bo::orbital_event::{OrbitalEvent, OrbitalEventKind};
+use crate::bo::simulation_options::EventOptions;
+
+// Précision de la date d'un événement (s)
+const DATE_TOLERANCE: f64 = 1e-3;
+const MAX_BISECTIONS: usize = 100;
+
+/// Position et vitesse d'un objet au début du pas
+struct Snapshot {
+  name: String,
+  position: [f64; 3],
+  velocity: [f64; 3],
+}
+
+/// Mouvement relatif d'un objet par rapport à un autre pendant un pas de `duration` secondes
+///
+/// Entre les positions et vitesses des deux extrémités, le mouvement est interpolé par
+/// un polynôme d'Hermite cubique, sans réévaluer les forces.
+pub struct Segment {
+  start: ([f64; 3], [f64; 3]),
+  end: ([f64; 3], [f64; 3]),
+  duration: f64,
+}
+
+impl Segment {
+  pub fn new(start: ([f64; 3], [f64; 3]), end: ([f64; 3], [f64; 3]), duration: f64) -> Self {
+    Segment { start, end, duration }
+  }
+
+  /// Position et vitesse relatives à la fraction `s` du pas
+  pub fn at(&self, s: f64) -> ([f64; 3], [f64; 3]) {
+    let ((p0, v0), (p1, v1), h) = (self.start, self.end, self.duration);
+    let (s2, s3) = (s * s, s * s * s);
+    let (h00, h10, h01, h11) = (2.0 * s3 - 3.0 * s2 + 1.0, s3 - 2.0 * s2 + s, -2.0 * s3 + 3.0 * s2, s3 - s2);
+    let (d00, d10, d01, d11) = (6.0 * s2 - 6.0 * s, 3.0 * s2 - 4.0 * s + 1.0, -6.0 * s2 + 6.0 * s, 3.0 * s2 - 2.0 * s);
+    (
+      std::array::from_fn(|k| h00 * p0[k] + h10 * h * v0[k] + h01 * p1[k] + h11 * h * v1[k]),
+      std::array::from_fn(|k| (d00 * p0[k] + d01 * p1[k]) / h + d10 * v0[k] + d11 * v1[k]),
+    )
+  }
+
+  /// Fraction du pas où `g`, fonction de la position et de la vitesse relatives, change de signe
+  fn crossing(&self, g: impl Fn(([f64; 3], [f64; 3])) -> f64) -> Option<(f64, bool)> {
+    root(self.duration, |s| g(self.at(s)))
+  }
+}
+
+/// Fraction d'un pas de `duration` secondes où `g`, fonction de la fraction du pas, change
+/// de signe, par dichotomie, et sens du changement dans l'ordre chronologique (`true` du
+/// négatif au positif) ; `None` sans changement de signe entre les extrémités
+pub fn root(duration: f64, g: impl Fn(f64) -> f64) -> Option<(f64, bool)> {
+  let (mut a, mut b) = (0.0, 1.0);
+  let (ga, gb) = (g(a), g(b));
+  // En remontant le temps, la fin du pas est l'extrémité la plus ancienne
+  let (earlier, later) = if duration > 0.0 { (ga, gb) } else { (gb, ga) };
+  let rising = earlier < 0.0 && later >= 0.0;
+  let falling = earlier > 0.0 && later <= 0.0;
+  if !(rising || falling) {
+    return None;
+  }
+
+  for _ in 0..MAX_BISECTIONS {
+    if (b - a) * duration.abs() < DATE_TOLERANCE {
+      break;
+    }
+    let middle = 0.5 * (a + b);
+    if (g(middle) < 0.0) == (ga < 0.0) {
+      a = middle;
+    } else {
+      b = middle;
+    }
+  }
+  Some((0.5 * (a + b), rising))
+}
+
+/// Recherche des événements orbitaux au fil de l'intégration
+///
+/// À chaque pas, les fonctions dont l'annulation marque un événement sont évaluées sur
+/// le mouvement relatif interpolé : r·v pour les apsides et les rapprochements (minimum
+/// de distance), la cote z pour les nœuds. Un changement de signe entre les deux
+/// extrémités du pas est daté par dichotomie. Le pas est borné par `max_step`, deux
+/// racines dans un même pas s'annulant. Les objets sont appariés par leur nom d'un bout
+/// à l'autre du pas, de sorte qu'une fusion n'introduit pas de faux événement.
+pub struct EventDetector {
+  options: Option<EventOptions>,
+  start: Vec<Snapshot>,
+  events: Vec<OrbitalEvent>,
+}
+
+impl EventDetector {
+  /// Détecteur des événements `options` des objets `items`, inactif sans options
+  pub fn new(options: Option<&EventOptions>, items: &[CelestItem]) -> Result<Self> {
+    if let Some(name) = options.and_then(|options| options.central_body.as_deref())
+      && !items.iter().any(|item| item.name == name)
+    {
+      bail!("corps central inconnu : {name}");
+    }
+    Ok(EventDetector { options: options.cloned(), start: Vec::new(), events: Vec::new() })
+  }
+
+  /// Pas `dt` réduit au pas maximal de la détection
+  pub fn bound(&self, dt: f64) -> f64 {
+    match &self.options {
+      Some(options) => dt.clamp(-options.max_step, options.max_step),
+      None => dt,
+    }
+  }
+
+  /// Mémorise l'état au début du pas
+  pub fn begin(&mut self, state: &SystemState, items: &[CelestItem]) {
+    if self.options.is_none() {
+      return;
+    }
+    self.start.clear();
+    self.start.extend(items.iter().enumerate().map(|(i, item)| Snapshot {
+      name: item.name.clone(),
+      position: state.positions.get(i),
+      velocity: state.velocities.get(i),
+    }));
+  }
+
+  /// Recherche les événements du pas de `duration` secondes commencé à `date`, `state` étant l'état en fin de pas
+  pub fn detect(&mut self, date: Epoch, duration: f64, state: &SystemState, items: &[CelestItem]) {
+    let Some(options) = &self.options else {
+      return;
+    };
+    if duration == 0.0 {
+      return;
+    }
+
+    // Objets présents aux deux extrémités du pas
+    let bodies: Vec<(usize, &Snapshot)> = items
+      .iter()
+      .enumerate()
+      .filter_map(|(i, item)| {
+        let start = self.start.get(i).filter(|start| start.name == item.name);
+        start.or_else(|| self.start.iter().find(|start| start.name == item.name)).map(|start| (i, start))
+      })
+      .collect();
+    let segment = |a: &(usize, &Snapshot), b: &(usize, &Snapshot)| {
+      let (end_a, end_b) = ((state.positions.get(a.0), state.velocities.get(a.0)), (state.positions.get(b.0), state.velocities.get(b.0)));
+      Segment::new(
+        (difference(a.1.position, b.1.position), difference(a.1.velocity, b.1.velocity)),
+        (difference(end_a.0, end_b.0), difference(end_a.1, end_b.1)),
+        duration,
+      )
+    };
+    let event = |kind, segment: &Segment, s: f64, names: [&str; 2]| {
+      let (r, v) = segment.at(s);
+      OrbitalEvent {
+        date: date.after(s * duration),
+        kind,
+        bodies: names.map(String::from),
+        distance: norm(r),
+        relative_speed: norm(v),
+      }
+    };
+
+    let central = match options.central_body.as_deref() {
+      Some(name) => bodies.iter().find(|(i, _)| items[*i].name == name),
+      None => dominant(&state.masses).and_then(|central| bodies.iter().find(|(i, _)| *i == central)),
+    };
+    if let Some(central) = central.filter(|_| options.apsides || options.nodes) {
+      for body in bodies.iter().filter(|(i, _)| *i != central.0) {
+        let segment = segment(body, central);
+        let names = [items[body.0].name.as_str(), items[central.0].name.as_str()];
+        if options.apsides
+          && let Some((s, rising)) = segment.crossing(|(r, v)| dot(r, v))
+        {
+          let kind = if rising { OrbitalEventKind::Periapsis } else { OrbitalEventKind::Apoapsis };
+          self.events.push(event(kind, &segment, s, names));
+        }
+        if options.nodes
+          && let Some((s, rising)) = segment.crossing(|(r, _)| r[2])
+        {
+          let kind = if rising { OrbitalEventKind::AscendingNode } else { OrbitalEventKind::DescendingNode };
+          self.events.push(event(kind, &segment, s, names));
+        }
+      }
+    }
+
+    if let Some(threshold) = options.close_approach {
+      for (k, a) in bodies.iter().enumerate() {
+        for b in &bodies[k + 1..] {
+          let segment = segment(a, b);
+          if let Some((s, true)) = segment.crossing(|(r, v)| dot(r, v)) {
+            let approach = event(OrbitalEventKind::CloseApproach, &segment, s, [&items[a.0].name, &items[b.0].name]);
+            if approach.distance < threshold {
+              self.events.push(approach);
+            }
+          }
+        }
+      }
+    }
+  }
+
+  /// Événements détectés, dans l'ordre chronologique
+  pub fn into_events(mut self) -> Vec<OrbitalEvent> {
+    self.events.sort_by_key(|event| event.date);
+    self.events
+  }
+}
+
+fn difference(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+  std::array::from_fn(|k| a[k] - b[k])
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+  dot(a, a).sqrt()
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::f64::consts::{PI, TAU};
+
+  use crate::bll::gravity::G;
+  use crate::bll::integrator::two_body::TwoBody;
+  use crate::bll::integrator::Integrator;
+  use crate::bll::time;
+  use crate::bo::orbital_elements::OrbitalElements;
+  use crate::bo::time_scale::TimeScale;
+
+  fn item(name: &str, mass: f64, position: [f64; 3], velocity: [f64; 3]) -> CelestItem {
+    CelestItem { name: name.into(), mass, radius: 1.0, position, velocity, test_particle: mass == 0.0, ..Default::default() }
+  }
+
+  #[test]
+  fn apsides_nodes_and_close_approaches_are_dated() {
+    let start = time::parse("2000-01-01T12:00:00Z", TimeScale::Utc).unwrap();
+
+    // Orbite inclinée de 30°, périapside au-dessus de l'écliptique, départ peu avant l'apoapside
+    let (sun, a, e, departure) = (1.9885e30, 1.5e11, 0.5, 170_f64.to_radians());
+    let mu = G * sun;
+    let elements = OrbitalElements {
+      semi_major_axis: a,
+      eccentricity: e,
+      inclination: 30.0,
+      longitude_of_ascending_node: 0.0,
+      argument_of_periapsis: 90.0,
+      mean_anomaly: departure.to_degrees(),
+      periapsis_distance: a * (1.0 - e),
+    };
+    let (position, velocity) = elements.to_state(mu);
+    let items = [item("Soleil", sun, [0.0; 3], [0.0; 3]), item("Comète", 0.0, position, velocity)];
+    let mut state = SystemState::from_items(&items);
+    let mut detector = EventDetector::new(Some(&EventOptions::default()), &items).unwrap();
+    let n = (mu / a.powi(3)).sqrt();
+    let (duration, mut elapsed) = (1.25 * TAU / n, 0.0);
+    while elapsed < duration {
+      let dt = detector.bound(duration - elapsed);
+      detector.begin(&state, &items);
+      let done = TwoBody.step(&mut state, dt).unwrap();
+      detector.detect(start.after(elapsed), done, &state, &items);
+      elapsed += done;
+    }
+
+    // Anomalies moyennes des passages successifs ; aux nœuds, l'anomalie vraie vaut ∓90°.
+    // Avec des pas d'un jour, l'interpolation date le périapside à quelques secondes près
+    let node = PI / 3.0 - e * (PI / 3.0).sin();
+    let expected = [
+      (OrbitalEventKind::Apoapsis, PI),
+      (OrbitalEventKind::AscendingNode, TAU - node),
+      (OrbitalEventKind::Periapsis, TAU),
+      (OrbitalEventKind::DescendingNode, TAU + node),
+      (OrbitalEventKind::Apoapsis, TAU + PI),
+    ];
+    let events = detector.into_events();
+    assert_eq!(events.len(), expected.len(), "{events:?}");
+    for (event, (kind, mean_anomaly)) in events.iter().zip(expected) {
+      let (at, found) = ((mean_anomaly - departure) / n, event.date.seconds_since(start));
+      assert_eq!(event.kind, kind);
+      assert_eq!(event.bodies, ["Comète".to_string(), "Soleil".to_string()]);
+      assert!((found - at).abs() < 5.0, "{kind:?} à {found} s au lieu de {at} s");
+    }
+
+    // Deux particules en ligne droite, au plus près à 50 m au bout de 1 000 s
+    let items = [item("A", 0.0, [-1000.0, 0.0, 0.0], [1.0, 0.0, 0.0]), item("B", 0.0, [0.0, 50.0, 0.0], [0.0; 3])];
+    let options = EventOptions { apsides: false, nodes: false, close_approach: Some(100.0), ..EventOptions::default() };
+    let mut state = SystemState::from_items(&items);
+    let mut detector = EventDetector::new(Some(&options), &items).unwrap();
+    detector.begin(&state, &items);
+    state.positions.set(0, [1000.0, 0.0, 0.0]);
+    detector.detect(start, 2000.0, &state, &items);
+    let events = detector.into_events();
+    assert_eq!(events.len(), 1, "{events:?}");
+    assert_eq!(events[0].kind, OrbitalEventKind::CloseApproach);
+    assert!((events[0].date.seconds_since(start) - 1000.0).abs() < 1e-2);
+    assert!((events[0].distance - 50.0).abs() < 1e-6 && (events[0].relative_speed - 1.0).abs() < 1e-9);
+    assert!(EventDetector::new(Some(&EventOptions { central_body: Some("Vénus".into()), ..options }), &items).is_err());
+  }
+}
@@ -0,0 +1,52 @@
+pub mod radiation_pressure;
+pub mod thrust;
+pub mod yarkovsky;
+
+use crate::bll::gravity::MIN_DISTANCE;
+use crate::bll::state::Vectors;
+use crate::bo::force_parameters::ForceParameters;
+
+/// Unité astronomique (m)
+pub const ASTRONOMICAL_UNIT: f64 = 1.495_978_707e11;
+
+/// Accélération additionnelle composée avec la gravitation dans `Dynamics`
+pub trait Force: Send + Sync {
+  /// Ajoute à `out` l'accélération subie par chaque corps, `parameters` étant indexé comme les positions
+  fn add_accelerations(
+    &self,
+    positions: &Vectors,
+    velocities: &Vectors,
+    masses: &[f64],
+    parameters: &[ForceParameters],
+    out: &mut Vectors,
+  );
+
+  /// Indique si l'accélération dépend des vitesses, et pas seulement des positions
+  fn depends_on_velocity(&self) -> bool;
+}
+
+/// Indice du corps le plus massif, source du rayonnement et référence des directions
+pub fn dominant(masses: &[f64]) -> Option<usize> {
+  (0..masses.len()).max_by(|&a, &b| masses[a].total_cmp(&masses[b]))
+}
+
+/// Position et vitesse du corps `i` relativement au corps `central`, `None` s'ils sont confondus
+pub fn relative(positions: &Vectors, velocities: &Vectors, i: usize, central: usize) -> Option<([f64; 3], [f64; 3])> {
+  let (pi, pc) = (positions.get(i), positions.get(central));
+  let r: [f64; 3] = std::array::from_fn(|k| pi[k] - pc[k]);
+  if r[0] * r[0] + r[1] * r[1] + r[2] * r[2] < MIN_DISTANCE * MIN_DISTANCE {
+    return None;
+  }
+  let (vi, vc) = (velocities.get(i), velocities.get(central));
+  Some((r, std::array::from_fn(|k| vi[k] - vc[k])))
+}
+
+/// Ajoute `magnitude · direction` (direction normalisée) à l'accélération du corps `i`
+fn push(out: &mut Vectors, i: usize, direction: [f64; 3], magnitude: f64) {
+  let norm = (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2]).sqrt();
+  if norm == 0.0 {
+    return;
+  }
+  let acceleration = out.get(i);
+  out.set(i, std::array::from_fn(|k| acceleration[k] + magnitude * direction[k] / norm));
+}
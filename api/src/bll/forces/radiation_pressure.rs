@@ -0,0 +1,68 @@
+use crate::bll::forces::{dominant, push, relative, Force, ASTRONOMICAL_UNIT};
+use crate::bll::state::Vectors;
+use crate::bo::force_parameters::ForceParameters;
+
+/// Pression du rayonnement solaire à 1 UA (N/m²), flux de 1361 W/m² divisé par c
+pub const SOLAR_PRESSURE: f64 = 4.56e-6;
+
+/// Pression de radiation du corps dominant, sans ombre portée
+///
+/// a = C_r · (A/m) · P · (1 UA / r)², dirigée à l'opposé de la source.
+pub struct RadiationPressure;
+
+impl Force for RadiationPressure {
+  fn add_accelerations(
+    &self,
+    positions: &Vectors,
+    velocities: &Vectors,
+    masses: &[f64],
+    parameters: &[ForceParameters],
+    out: &mut Vectors,
+  ) {
+    let Some(source) = dominant(masses) else {
+      return;
+    };
+    for (i, body) in parameters.iter().enumerate() {
+      if i == source || body.area_to_mass == 0.0 {
+        continue;
+      }
+      let Some((r, _)) = relative(positions, velocities, i, source) else {
+        continue;
+      };
+      let r_squared = r[0] * r[0] + r[1] * r[1] + r[2] * r[2];
+      let magnitude = body.reflectivity * body.area_to_mass * SOLAR_PRESSURE * ASTRONOMICAL_UNIT * ASTRONOMICAL_UNIT / r_squared;
+      push(out, i, r, magnitude);
+    }
+  }
+
+  fn depends_on_velocity(&self) -> bool {
+    false
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn inverse_square_push_away_from_the_dominant_body() {
+    let mut positions = Vectors::zeros(3);
+    positions.set(0, [1e9, 0.0, 0.0]);
+    positions.set(1, [1e9, 2.0 * ASTRONOMICAL_UNIT, 0.0]);
+    positions.set(2, [1e9, -ASTRONOMICAL_UNIT, 0.0]);
+    let sail = ForceParameters { area_to_mass: 0.01, reflectivity: 1.5, ..Default::default() };
+    let parameters = [sail, sail, ForceParameters::default()];
+    let mut out = Vectors::zeros(3);
+    out.set(1, [1.0, 0.0, 0.0]);
+
+    RadiationPressure.add_accelerations(&positions, &Vectors::zeros(3), &[2e30, 1e3, 1e3], &parameters, &mut out);
+
+    // C_r · A/m · P à 2 UA : le quart de la pression à 1 UA, ajouté à l'accélération existante
+    let expected = 1.5 * 0.01 * SOLAR_PRESSURE / 4.0;
+    let acceleration = out.get(1);
+    assert!(acceleration[0] == 1.0 && (acceleration[1] - expected).abs() < 1e-15 * expected && acceleration[2] == 0.0);
+    // La source et les corps sans surface exposée ne subissent rien
+    assert_eq!((out.get(0), out.get(2)), ([0.0; 3], [0.0; 3]));
+  }
+}
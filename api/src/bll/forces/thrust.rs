@@ -0,0 +1,83 @@
+use crate::bll::forces::{dominant, push, relative, Force};
+use crate::bll::state::Vectors;
+use crate::bo::force_parameters::{ForceParameters, ThrustDirection};
+
+/// Poussée continue d'accélération constante, orientée dans le repère orbital du corps dominant
+pub struct ConstantThrust;
+
+impl Force for ConstantThrust {
+  fn add_accelerations(
+    &self,
+    positions: &Vectors,
+    velocities: &Vectors,
+    masses: &[f64],
+    parameters: &[ForceParameters],
+    out: &mut Vectors,
+  ) {
+    let Some(central) = dominant(masses) else {
+      return;
+    };
+    for (i, body) in parameters.iter().enumerate() {
+      let Some(thrust) = body.thrust else {
+        continue;
+      };
+      if i == central {
+        continue;
+      }
+      let Some((r, v)) = relative(positions, velocities, i, central) else {
+        continue;
+      };
+      let h = [r[1] * v[2] - r[2] * v[1], r[2] * v[0] - r[0] * v[2], r[0] * v[1] - r[1] * v[0]];
+      let (direction, sign) = match thrust.direction {
+        ThrustDirection::Prograde => (v, 1.0),
+        ThrustDirection::Retrograde => (v, -1.0),
+        ThrustDirection::Radial => (r, 1.0),
+        ThrustDirection::AntiRadial => (r, -1.0),
+        ThrustDirection::Normal => (h, 1.0),
+        ThrustDirection::AntiNormal => (h, -1.0),
+        ThrustDirection::Inertial(direction) => (direction, 1.0),
+      };
+      push(out, i, direction, sign * thrust.acceleration);
+    }
+  }
+
+  fn depends_on_velocity(&self) -> bool {
+    true
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bo::force_parameters::Thrust;
+
+  #[test]
+  fn constant_acceleration_in_the_orbital_frame() {
+    // Engin sur x, en mouvement selon y relativement à un corps dominant lui-même en mouvement
+    let (mut positions, mut velocities) = (Vectors::zeros(2), Vectors::zeros(2));
+    positions.set(0, [-5e8, 0.0, 0.0]);
+    positions.set(1, [1e11, 0.0, 0.0]);
+    velocities.set(0, [10.0, 100.0, 0.0]);
+    velocities.set(1, [10.0, 3e4, 0.0]);
+    let acceleration = 1e-3;
+
+    for (direction, expected) in [
+      (ThrustDirection::Prograde, [0.0, 1.0, 0.0]),
+      (ThrustDirection::Retrograde, [0.0, -1.0, 0.0]),
+      (ThrustDirection::Radial, [1.0, 0.0, 0.0]),
+      (ThrustDirection::AntiRadial, [-1.0, 0.0, 0.0]),
+      (ThrustDirection::Normal, [0.0, 0.0, 1.0]),
+      (ThrustDirection::AntiNormal, [0.0, 0.0, -1.0]),
+      (ThrustDirection::Inertial([3.0, 4.0, 0.0]), [0.6, 0.8, 0.0]),
+    ] {
+      let engine = ForceParameters { thrust: Some(Thrust { acceleration, direction }), ..Default::default() };
+      let mut out = Vectors::zeros(2);
+      ConstantThrust.add_accelerations(&positions, &velocities, &[2e30, 1e3], &[engine, engine], &mut out);
+      let got = out.get(1);
+      assert!((0..3).all(|k| (got[k] - acceleration * expected[k]).abs() < 1e-18), "{direction:?} {got:?}");
+      // Le corps dominant ne se pousse pas lui-même
+      assert_eq!(out.get(0), [0.0; 3]);
+    }
+  }
+}
@@ -0,0 +1,99 @@
+use crate::bll::forces::{dominant, push, relative, Force, ASTRONOMICAL_UNIT};
+use crate::bll::state::Vectors;
+use crate::bo::force_parameters::ForceParameters;
+
+/// Effet Yarkovsky sous sa forme transverse usuelle
+///
+/// a = A2 · (1 UA / r)² selon la composante de la vitesse héliocentrique
+/// perpendiculaire au rayon vecteur : un A2 positif fait dériver le demi-grand
+/// axe vers l'extérieur, un A2 négatif vers l'intérieur.
+pub struct Yarkovsky;
+
+impl Force for Yarkovsky {
+  fn add_accelerations(
+    &self,
+    positions: &Vectors,
+    velocities: &Vectors,
+    masses: &[f64],
+    parameters: &[ForceParameters],
+    out: &mut Vectors,
+  ) {
+    let Some(source) = dominant(masses) else {
+      return;
+    };
+    for (i, body) in parameters.iter().enumerate() {
+      if i == source || body.yarkovsky == 0.0 {
+        continue;
+      }
+      let Some((r, v)) = relative(positions, velocities, i, source) else {
+        continue;
+      };
+      let r_squared = r[0] * r[0] + r[1] * r[1] + r[2] * r[2];
+      let radial_speed = (r[0] * v[0] + r[1] * v[1] + r[2] * v[2]) / r_squared;
+      let transverse: [f64; 3] = std::array::from_fn(|k| v[k] - radial_speed * r[k]);
+      push(out, i, transverse, body.yarkovsky * ASTRONOMICAL_UNIT * ASTRONOMICAL_UNIT / r_squared);
+    }
+  }
+
+  fn depends_on_velocity(&self) -> bool {
+    true
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::bll::gravity::G;
+  use crate::bll::integrator;
+  use crate::bll::state::SystemState;
+  use crate::bo::celest_item::CelestItem;
+  use crate::bo::force_parameters::ForceParameters;
+  use crate::bo::simulation_options::{IntegratorKind, SimulationOptions};
+
+  use super::*;
+
+  const SUN_MASS: f64 = 1.98847e30;
+  const YEAR: f64 = 365.25 * 86400.0;
+
+  /// Demi-grand axe héliocentrique (m), par l'intégrale de l'énergie
+  fn semi_major_axis(state: &SystemState) -> f64 {
+    let (ps, pa) = (state.positions.get(0), state.positions.get(1));
+    let (vs, va) = (state.velocities.get(0), state.velocities.get(1));
+    let r = (0..3).map(|k| (pa[k] - ps[k]).powi(2)).sum::<f64>().sqrt();
+    let v_squared: f64 = (0..3).map(|k| (va[k] - vs[k]).powi(2)).sum();
+    1.0 / (2.0 / r - v_squared / (G * SUN_MASS))
+  }
+
+  #[test]
+  fn circular_orbit_drifts_at_two_a2_over_n() {
+    let a = ASTRONOMICAL_UNIT;
+    let a2 = 1e-12;
+    let items = vec![
+      CelestItem { name: "Soleil".into(), mass: SUN_MASS, radius: 1.0, fixed: true, ..Default::default() },
+      CelestItem {
+        name: "Astéroïde".into(),
+        mass: 1e12,
+        radius: 1.0,
+        position: [a, 0.0, 0.0],
+        velocity: [0.0, (G * SUN_MASS / a).sqrt(), 0.0],
+        forces: Some(ForceParameters { yarkovsky: a2, ..Default::default() }),
+        ..Default::default()
+      },
+    ];
+
+    let options = SimulationOptions { integrator: IntegratorKind::Ias15, ..Default::default() };
+    let mut state = SystemState::from_items(&items);
+    let mut integrator = integrator::build(&options, &state);
+    let duration = 10.0 * YEAR;
+    let mut elapsed = 0.0;
+    while elapsed < duration {
+      elapsed += integrator.step(&mut state, duration - elapsed).unwrap();
+    }
+
+    // da/dt = 2 A2 / n sur une orbite circulaire
+    let mean_motion = (G * SUN_MASS / (a * a * a)).sqrt();
+    let expected = 2.0 * a2 / mean_motion * duration;
+    let drift = semi_major_axis(&state) - a;
+    assert!((drift / expected - 1.0).abs() < 0.01, "dérive {drift} m, attendue {expected} m");
+  }
+}
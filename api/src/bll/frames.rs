@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use anyhow::{Context, Result};
+
+use crate::bll::ephemeris::vsop87;
+use crate::bo::celest_item::CelestItem;
+use crate::bo::epoch::Epoch;
+use crate::bo::reference_frame::{FrameOrigin, ReferenceFrame, ReferencePlane};
+use crate::bo::simulation_result::SimulationResult;
+use crate::bo::utils::ecliptic_to_equatorial;
+
+/// Ramène les objets dans le repère du barycentre du système
+///
+/// Le centre de masse est placé à l'origine et sa vitesse annulée, de sorte que la
+/// quantité de mouvement totale soit nulle. Si un objet est maintenu fixe, c'est lui
+/// qui ancre le repère : les conditions initiales sont laissées telles quelles.
+/// Les particules test sont déplacées avec le repère sans peser sur le barycentre.
+pub fn to_barycentric(items: &mut [CelestItem]) {
+  if items.iter().any(|item| item.fixed) {
+    return;
+  }
+
+  let massive = || items.iter().filter(|item| !item.test_particle);
+  let total_mass: f64 = massive().map(|item| item.mass).sum();
+  if total_mass <= 0.0 {
+    return;
+  }
+
+  let mut center = [0.0; 3];
+  let mut center_velocity = [0.0; 3];
+  for item in massive() {
+    for k in 0..3 {
+      center[k] += item.mass * item.position[k] / total_mass;
+      center_velocity[k] += item.mass * item.velocity[k] / total_mass;
+    }
+  }
+
+  for item in items.iter_mut() {
+    for k in 0..3 {
+      item.position[k] -= center[k];
+      item.velocity[k] -= center_velocity[k];
+    }
+  }
+}
+
+/// Exprime les objets du résultat dans le repère `frame`
+///
+/// L'origine est prise à la date de chaque objet : barycentre des corps massifs, Soleil
+/// ou objet désigné, dont la vitesse est aussi retranchée. Les axes équatoriaux sont
+/// ceux de l'ICRF, déduits de l'écliptique J2000 par l'obliquité ; le biais de quelques
+/// dizaines de millisecondes d'arc entre les deux repères est négligé.
+pub fn to_frame(result: &mut SimulationResult, frame: &ReferenceFrame) -> Result<()> {
+  let items = [result.celest_items.as_slice(), &result.test_particles].concat();
+  let mut origins = HashMap::new();
+  for item in &items {
+    if let Entry::Vacant(entry) = origins.entry(item.timestamp) {
+      entry.insert(origin(&items, item.timestamp, &frame.origin)?);
+    }
+  }
+
+  let rotate = |vector: [f64; 3]| match frame.plane {
+    ReferencePlane::Ecliptic => vector,
+    ReferencePlane::Equatorial => ecliptic_to_equatorial(vector),
+  };
+  for item in result.celest_items.iter_mut().chain(result.test_particles.iter_mut()) {
+    let (position, velocity) = origins[&item.timestamp];
+    item.position = rotate(std::array::from_fn(|k| item.position[k] - position[k]));
+    item.velocity = rotate(std::array::from_fn(|k| item.velocity[k] - velocity[k]));
+  }
+  Ok(())
+}
+
+/// Position et vitesse de l'origine du repère parmi les objets datés de `timestamp`
+fn origin(items: &[CelestItem], timestamp: Option<Epoch>, origin: &FrameOrigin) -> Result<([f64; 3], [f64; 3])> {
+  let mut at_date = items.iter().filter(|item| item.timestamp == timestamp);
+  let body = match origin {
+    FrameOrigin::Barycentric => {
+      let massive: Vec<_> = at_date.filter(|item| !item.test_particle).collect();
+      let total_mass: f64 = massive.iter().map(|item| item.mass).sum();
+      if total_mass <= 0.0 {
+        return Ok(([0.0; 3], [0.0; 3]));
+      }
+      let weighted = |vector: fn(&CelestItem) -> [f64; 3]| -> [f64; 3] {
+        std::array::from_fn(|k| massive.iter().map(|item| item.mass * vector(item)[k]).sum::<f64>() / total_mass)
+      };
+      return Ok((weighted(|item| item.position), weighted(|item| item.velocity)));
+    }
+    FrameOrigin::Heliocentric => at_date.find(|item| vsop87::is_sun(&item.name)).context("le Soleil est absent de la simulation")?,
+    FrameOrigin::Body(name) => at_date.find(|item| &item.name == name).with_context(|| format!("objet inconnu : {name}"))?,
+  };
+  Ok((body.position, body.velocity))
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+  [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn unit(a: [f64; 3]) -> [f64; 3] {
+  let norm = (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt();
+  if norm > 0.0 { a.map(|x| x / norm) } else { [0.0; 3] }
+}
+
+/// Exprime dans les axes de la simulation un vecteur donné dans le repère VNB de l'orbite (r, v)
+///
+/// V suit la vitesse, N le moment cinétique r × v, et B = V × N complète le trièdre direct.
+pub fn vnb_to_inertial(r: [f64; 3], v: [f64; 3], components: [f64; 3]) -> [f64; 3] {
+  let along = unit(v);
+  let normal = unit(cross(r, v));
+  let binormal = cross(along, normal);
+  std::array::from_fn(|k| components[0] * along[k] + components[1] * normal[k] + components[2] * binormal[k])
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bo::utils::{equatorial_to_ecliptic, OBLIQUITY_J2000};
+
+  fn item(name: &str, mass: f64, position: [f64; 3], velocity: [f64; 3], test_particle: bool) -> CelestItem {
+    CelestItem { name: name.into(), mass, radius: 1.0, position, velocity, test_particle, ..Default::default() }
+  }
+
+  #[test]
+  fn origins_and_planes() {
+    let result = SimulationResult {
+      celest_items: vec![
+        item("Sun", 3.0, [1.0, 2.0, 0.0], [0.0, 1.0, 0.0], false),
+        item("Terre", 1.0, [5.0, 2.0, 4.0], [0.0, 5.0, 0.0], false),
+      ],
+      test_particles: vec![item("Sonde", 0.0, [9.0, 0.0, 0.0], [0.0, 0.0, 0.0], true)],
+      stats: None,
+      diagnostics: None,
+      collisions: vec![],
+      events: vec![],
+      orbital_elements: vec![],
+    };
+    let convert = |origin: FrameOrigin, plane: ReferencePlane| {
+      let mut result = result.clone();
+      to_frame(&mut result, &ReferenceFrame { origin, plane }).map(|_| result)
+    };
+
+    // Le barycentre ignore la particule test, le Soleil est reconnu sous son nom anglais
+    let barycentric = convert(FrameOrigin::Barycentric, ReferencePlane::Ecliptic).unwrap();
+    assert_eq!(barycentric.celest_items[0].position, [-1.0, 0.0, -1.0]);
+    assert_eq!(barycentric.test_particles[0].velocity, [0.0, -2.0, 0.0]);
+    let heliocentric = convert(FrameOrigin::Heliocentric, ReferencePlane::Ecliptic).unwrap();
+    assert_eq!(heliocentric.celest_items[1].position, [4.0, 0.0, 4.0]);
+    let probe = convert(FrameOrigin::Body("Sonde".into()), ReferencePlane::Ecliptic).unwrap();
+    assert_eq!(probe.test_particles[0].position, [0.0; 3]);
+    assert!(convert(FrameOrigin::Body("Lune".into()), ReferencePlane::Ecliptic).is_err());
+
+    // Le pôle de l'écliptique est à 23,44° du pôle céleste, du côté des y équatoriaux négatifs
+    let equatorial = convert(FrameOrigin::Heliocentric, ReferencePlane::Equatorial).unwrap();
+    let pole = equatorial.celest_items[1].position.map(|x| x / 4.0);
+    let obliquity = OBLIQUITY_J2000.to_radians();
+    assert!((pole[0] - 1.0).abs() < 1e-12 && (pole[1] + obliquity.sin()).abs() < 1e-12 && (pole[2] - obliquity.cos()).abs() < 1e-12);
+    assert_eq!(equatorial_to_ecliptic(equatorial.celest_items[1].velocity).map(|x| x.round()), [0.0, 4.0, 0.0]);
+  }
+
+  #[test]
+  fn barycentric_frame_cancels_total_momentum_unless_anchored() {
+    let items = vec![
+      item("Soleil", 1000.0, [1.0, -2.0, 0.5], [0.3, 0.1, -0.2], false),
+      item("Terre", 3.0, [150.0, 4.0, -1.0], [0.0, 30.0, 1.0], false),
+      item("Jupiter", 10.0, [-700.0, 80.0, 3.0], [-2.0, -13.0, 0.0], false),
+      item("Sonde", 0.0, [40.0, 40.0, 40.0], [5.0, 0.0, 0.0], true),
+    ];
+
+    let mut barycentric = items.clone();
+    to_barycentric(&mut barycentric);
+    let total = |vector: fn(&CelestItem) -> [f64; 3]| -> [f64; 3] {
+      std::array::from_fn(|k| barycentric.iter().map(|item| item.mass * vector(item)[k]).sum())
+    };
+    assert!(total(|item| item.velocity).iter().all(|p| p.abs() < 1e-12), "{:?}", total(|item| item.velocity));
+    assert!(total(|item| item.position).iter().all(|x| x.abs() < 1e-9), "{:?}", total(|item| item.position));
+    // Les écarts relatifs, dont ceux de la particule test, sont préservés
+    for (before, after) in items.iter().zip(&barycentric) {
+      let shift: [f64; 3] = std::array::from_fn(|k| before.velocity[k] - after.velocity[k]);
+      let reference: [f64; 3] = std::array::from_fn(|k| items[0].velocity[k] - barycentric[0].velocity[k]);
+      assert!((0..3).all(|k| (shift[k] - reference[k]).abs() < 1e-12));
+    }
+
+    // Un objet fixe ancre le repère : rien n'est déplacé
+    let mut anchored = items.clone();
+    anchored[0].fixed = true;
+    to_barycentric(&mut anchored);
+    for (before, after) in items.iter().zip(&anchored) {
+      assert_eq!((before.position, before.velocity), (after.position, after.velocity));
+    }
+  }
+}
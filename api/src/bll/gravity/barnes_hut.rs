@@ -0,0 +1,306 @@
+use rayon::prelude::*;
+
+use crate::bll::gravity::force_law::{ForceLaw, Newtonian};
+use crate::bll::gravity::{ForceSolver, G};
+use crate::bll::state::Vectors;
+
+// Au-delà de cette profondeur, les corps confondus partagent une même feuille
+const MAX_DEPTH: usize = 32;
+// Index d'enfant absent (la racine n'est jamais un enfant)
+const NONE: usize = 0;
+
+struct Node {
+  center: [f64; 3],
+  half_size: f64,
+  mass: f64,
+  center_of_mass: [f64; 3],
+  internal: bool,
+  children: [usize; 8],
+  bodies: Vec<usize>,
+}
+
+impl Node {
+  fn new(center: [f64; 3], half_size: f64) -> Self {
+    Node {
+      center,
+      half_size,
+      mass: 0.0,
+      center_of_mass: [0.0; 3],
+      internal: false,
+      children: [NONE; 8],
+      bodies: Vec::new(),
+    }
+  }
+
+  fn octant(&self, p: &[f64; 3]) -> usize {
+    (p[0] >= self.center[0]) as usize | ((p[1] >= self.center[1]) as usize) << 1 | ((p[2] >= self.center[2]) as usize) << 2
+  }
+
+  fn contains(&self, p: &[f64; 3]) -> bool {
+    (0..3).all(|k| (p[k] - self.center[k]).abs() <= self.half_size)
+  }
+}
+
+/// Octree de Barnes–Hut construit sur un jeu de positions
+struct Octree<'a> {
+  nodes: Vec<Node>,
+  positions: Vec<[f64; 3]>,
+  masses: &'a [f64],
+}
+
+impl<'a> Octree<'a> {
+  fn new(positions: &Vectors, masses: &'a [f64]) -> Self {
+    let positions: Vec<[f64; 3]> = (0..positions.len()).map(|i| positions.get(i)).collect();
+    let mut min = [f64::MAX; 3];
+    let mut max = [f64::MIN; 3];
+    for p in &positions {
+      for k in 0..3 {
+        min[k] = min[k].min(p[k]);
+        max[k] = max[k].max(p[k]);
+      }
+    }
+    let center = std::array::from_fn(|k| 0.5 * (min[k] + max[k]));
+    let extent = (0..3).map(|k| max[k] - min[k]).fold(0.0, f64::max);
+    let half_size = 0.5 * extent * (1.0 + 1e-9) + 1.0;
+
+    let mut tree = Octree { nodes: vec![Node::new(center, half_size)], positions, masses };
+    // Seules les sources sont rangées dans l'arbre, les particules test y sont évaluées
+    for body in 0..masses.len() {
+      tree.insert(0, body, 0);
+    }
+    tree.compute_moments();
+    tree
+  }
+
+  fn insert(&mut self, node: usize, body: usize, depth: usize) {
+    if self.nodes[node].internal {
+      let octant = self.nodes[node].octant(&self.positions[body]);
+      let child = self.child(node, octant);
+      self.insert(child, body, depth + 1);
+      return;
+    }
+
+    self.nodes[node].bodies.push(body);
+    if self.nodes[node].bodies.len() > 1 && depth < MAX_DEPTH {
+      // Subdivision de la feuille et redistribution de ses corps
+      let bodies = std::mem::take(&mut self.nodes[node].bodies);
+      self.nodes[node].internal = true;
+      for b in bodies {
+        self.insert(node, b, depth);
+      }
+    }
+  }
+
+  fn child(&mut self, node: usize, octant: usize) -> usize {
+    if self.nodes[node].children[octant] == NONE {
+      let parent = &self.nodes[node];
+      let quarter = 0.5 * parent.half_size;
+      let center = std::array::from_fn(|k| {
+        if octant >> k & 1 == 1 { parent.center[k] + quarter } else { parent.center[k] - quarter }
+      });
+      self.nodes.push(Node::new(center, quarter));
+      let index = self.nodes.len() - 1;
+      self.nodes[node].children[octant] = index;
+    }
+    self.nodes[node].children[octant]
+  }
+
+  /// Masse et centre de masse de chaque nœud (les enfants sont toujours créés après leur parent)
+  fn compute_moments(&mut self) {
+    for n in (0..self.nodes.len()).rev() {
+      let mut mass = 0.0;
+      let mut weighted = [0.0; 3];
+      if self.nodes[n].internal {
+        for &child in self.nodes[n].children.iter().filter(|&&c| c != NONE) {
+          let child = &self.nodes[child];
+          mass += child.mass;
+          for (w, c) in weighted.iter_mut().zip(child.center_of_mass) {
+            *w += child.mass * c;
+          }
+        }
+      } else {
+        for &body in &self.nodes[n].bodies {
+          mass += self.masses[body];
+          for (w, p) in weighted.iter_mut().zip(self.positions[body]) {
+            *w += self.masses[body] * p;
+          }
+        }
+      }
+      let node = &mut self.nodes[n];
+      node.mass = mass;
+      node.center_of_mass = if mass > 0.0 { weighted.map(|x| x / mass) } else { node.center };
+    }
+  }
+
+  fn acceleration<L: ForceLaw>(&self, body: usize, opening_angle: f64, law: &L) -> [f64; 3] {
+    let p = &self.positions[body];
+    let mut acc = [0.0; 3];
+    let mut add = |source: &[f64; 3], mass: f64| {
+      let d = [source[0] - p[0], source[1] - p[1], source[2] - p[2]];
+      let r_squared = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+      let factor = law.pair_factor(r_squared, mass);
+      for k in 0..3 {
+        acc[k] += factor * d[k];
+      }
+    };
+
+    let mut stack = vec![0];
+    while let Some(n) = stack.pop() {
+      let node = &self.nodes[n];
+      if node.mass == 0.0 {
+        continue;
+      }
+
+      if !node.internal {
+        for &other in node.bodies.iter().filter(|&&other| other != body) {
+          add(&self.positions[other], self.masses[other]);
+        }
+        continue;
+      }
+
+      // Critère d'ouverture : taille du nœud / distance au centre de masse < θ
+      let d = [
+        node.center_of_mass[0] - p[0],
+        node.center_of_mass[1] - p[1],
+        node.center_of_mass[2] - p[2],
+      ];
+      let distance = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+      if !node.contains(p) && 2.0 * node.half_size < opening_angle * distance {
+        add(&node.center_of_mass, node.mass);
+      } else {
+        stack.extend(node.children.iter().filter(|&&c| c != NONE));
+      }
+    }
+    law.field(acc.map(|a| G * a))
+  }
+}
+
+/// Approximation de Barnes–Hut en O(N log N)
+///
+/// Les corps sont rangés dans un octree ; un nœud suffisamment éloigné
+/// (taille / distance < angle d'ouverture θ) agit comme une masse ponctuelle
+/// placée en son centre de masse. θ = 0 redonne la sommation directe.
+pub struct BarnesHut<L: ForceLaw = Newtonian> {
+  opening_angle: f64,
+  law: L,
+}
+
+impl<L: ForceLaw> BarnesHut<L> {
+  pub fn new(opening_angle: f64, law: L) -> Self {
+    BarnesHut { opening_angle, law }
+  }
+}
+
+impl<L: ForceLaw> ForceSolver for BarnesHut<L> {
+  fn accelerations(&self, positions: &Vectors, masses: &[f64], out: &mut Vectors) {
+    out.resize(positions.len());
+    if positions.is_empty() {
+      return;
+    }
+    let tree = Octree::new(positions, masses);
+    let (ax, ay, az) = out.components_mut();
+    ax.par_iter_mut()
+      .zip(ay.par_iter_mut())
+      .zip(az.par_iter_mut())
+      .enumerate()
+      .for_each(|(body, ((ax, ay), az))| {
+        [*ax, *ay, *az] = tree.acceleration(body, self.opening_angle, &self.law);
+      });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bll::gravity::direct::DirectSummation;
+
+  /// Nuage pseudo-aléatoire reproductible de `n` corps dans une sphère de 1 UA
+  fn cloud(n: usize) -> (Vectors, Vec<f64>) {
+    let mut seed: u64 = 42;
+    let mut next = || {
+      seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+      (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let mut positions = Vec::with_capacity(n);
+    let mut masses = Vec::with_capacity(n);
+    while positions.len() < n {
+      let p = [2.0 * next() - 1.0, 2.0 * next() - 1.0, 2.0 * next() - 1.0];
+      if p.iter().map(|x| x * x).sum::<f64>() <= 1.0 {
+        positions.push(p.map(|x| x * 1.496e11));
+        masses.push(1e22 * (1.0 + 9.0 * next()));
+      }
+    }
+    let mut vectors = Vectors::zeros(n);
+    for (i, p) in positions.into_iter().enumerate() {
+      vectors.set(i, p);
+    }
+    (vectors, masses)
+  }
+
+  fn accelerations(solver: &dyn ForceSolver, positions: &Vectors, masses: &[f64]) -> Vec<[f64; 3]> {
+    let mut out = Vectors::default();
+    solver.accelerations(positions, masses, &mut out);
+    (0..out.len()).map(|i| out.get(i)).collect()
+  }
+
+  fn relative_errors(approx: &[[f64; 3]], exact: &[[f64; 3]]) -> Vec<f64> {
+    approx
+      .iter()
+      .zip(exact)
+      .map(|(a, e)| {
+        let diff = (0..3).map(|k| (a[k] - e[k]).powi(2)).sum::<f64>().sqrt();
+        diff / e.iter().map(|x| x * x).sum::<f64>().sqrt()
+      })
+      .collect()
+  }
+
+  #[test]
+  fn zero_opening_angle_matches_direct_sum() {
+    let (positions, masses) = cloud(500);
+    let exact = accelerations(&DirectSummation::new(Newtonian), &positions, &masses);
+    let approx = accelerations(&BarnesHut::new(0.0, Newtonian), &positions, &masses);
+
+    let max_error = relative_errors(&approx, &exact).into_iter().fold(0.0, f64::max);
+    assert!(max_error < 1e-12, "erreur maximale {max_error:e}");
+  }
+
+  #[test]
+  fn accuracy_against_direct_sum() {
+    let (positions, masses) = cloud(3000);
+    let exact = accelerations(&DirectSummation::new(Newtonian), &positions, &masses);
+
+    let mut previous_rms = 0.0;
+    for (opening_angle, max_rms) in [(0.3, 4e-3), (0.5, 1.5e-2), (0.8, 5e-2)] {
+      let approx = accelerations(&BarnesHut::new(opening_angle, Newtonian), &positions, &masses);
+      let errors = relative_errors(&approx, &exact);
+      let rms = (errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64).sqrt();
+      assert!(rms < max_rms, "θ = {opening_angle} : erreur RMS {rms:e}");
+      assert!(rms >= previous_rms, "l'erreur doit croître avec θ");
+      previous_rms = rms;
+    }
+  }
+
+  #[test]
+  fn test_particles_feel_sources_without_acting() {
+    // 50 sources suivies de 450 particules test, comparées à une sommation où leur masse est nulle
+    let (positions, mut masses) = cloud(500);
+    masses[50..].fill(0.0);
+    let exact = accelerations(&DirectSummation::new(Newtonian), &positions, &masses);
+    for solver in [&DirectSummation::new(Newtonian) as &dyn ForceSolver, &BarnesHut::new(0.0, Newtonian)] {
+      let approx = accelerations(solver, &positions, &masses[..50]);
+      let max_error = relative_errors(&approx, &exact).into_iter().fold(0.0, f64::max);
+      assert!(max_error < 1e-12, "erreur maximale {max_error:e}");
+    }
+  }
+
+  #[test]
+  fn coincident_bodies_do_not_recurse_forever() {
+    let mut positions = Vectors::zeros(4);
+    for i in 0..4 {
+      positions.set(i, [1e11, 0.0, 0.0]);
+    }
+    let masses = vec![1e24; 4];
+    let accelerations = accelerations(&BarnesHut::new(0.5, Newtonian), &positions, &masses);
+    assert!(accelerations.iter().all(|a| a.iter().all(|x| *x == 0.0)));
+  }
+}
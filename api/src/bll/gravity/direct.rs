@@ -0,0 +1,62 @@
+use rayon::prelude::*;
+
+use crate::bll::gravity::force_law::{ForceLaw, Newtonian};
+use crate::bll::gravity::{ForceSolver, G};
+use crate::bll::state::Vectors;
+
+// En deçà, le découpage en tâches coûte plus cher que le calcul lui-même
+const PARALLEL_THRESHOLD: usize = 256;
+
+/// Sommation directe en O(N²), parallélisée par corps
+///
+/// La boucle interne parcourt des tableaux contigus sans branchement
+/// (la coupure à courte distance est un simple masque), ce qui la rend vectorisable.
+pub struct DirectSummation<L: ForceLaw = Newtonian> {
+  law: L,
+}
+
+impl<L: ForceLaw> DirectSummation<L> {
+  pub fn new(law: L) -> Self {
+    DirectSummation { law }
+  }
+
+  fn acceleration(&self, xs: &[f64], ys: &[f64], zs: &[f64], masses: &[f64], i: usize) -> [f64; 3] {
+    let (xi, yi, zi) = (xs[i], ys[i], zs[i]);
+    let (mut ax, mut ay, mut az) = (0.0, 0.0, 0.0);
+    for (((x, y), z), m) in xs.iter().zip(ys).zip(zs).zip(masses) {
+      let dx = x - xi;
+      let dy = y - yi;
+      let dz = z - zi;
+      let r_squared = dx * dx + dy * dy + dz * dz;
+      // Le corps lui-même (r = 0) ne contribue pas : d = 0
+      let factor = self.law.pair_factor(r_squared, *m);
+      ax += factor * dx;
+      ay += factor * dy;
+      az += factor * dz;
+    }
+    self.law.field([G * ax, G * ay, G * az])
+  }
+}
+
+impl<L: ForceLaw> ForceSolver for DirectSummation<L> {
+  fn accelerations(&self, positions: &Vectors, masses: &[f64], out: &mut Vectors) {
+    out.resize(positions.len());
+    let (xs, ys, zs) = positions.components();
+    let (ax, ay, az) = out.components_mut();
+
+    if positions.len() < PARALLEL_THRESHOLD {
+      for i in 0..positions.len() {
+        [ax[i], ay[i], az[i]] = self.acceleration(xs, ys, zs, masses, i);
+      }
+      return;
+    }
+
+    ax.par_iter_mut()
+      .zip(ay.par_iter_mut())
+      .zip(az.par_iter_mut())
+      .enumerate()
+      .for_each(|(i, ((ax, ay), az))| {
+        [*ax, *ay, *az] = self.acceleration(xs, ys, zs, masses, i);
+      });
+  }
+}
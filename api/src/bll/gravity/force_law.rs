@@ -0,0 +1,164 @@
+use crate::bll::gravity::MIN_DISTANCE;
+
+const MIN_DISTANCE_SQUARED: f64 = MIN_DISTANCE * MIN_DISTANCE;
+
+/// Loi d'interaction gravitationnelle entre deux masses
+///
+/// L'accélération subie par un corps est `field(G · Σ f·d)`, où `d` est le vecteur
+/// vers chaque source et `f = pair_factor(|d|², m)`. Les solveurs sont génériques
+/// sur la loi afin que la boucle interne reste sans appel dynamique.
+pub trait ForceLaw: Send + Sync + Copy + 'static {
+  /// Facteur f (sans G) de la contribution d'une masse `mass` à la distance √`r_squared`
+  fn pair_factor(&self, r_squared: f64, mass: f64) -> f64;
+
+  /// Correction appliquée au champ total d'un corps (identité pour les lois linéaires)
+  #[inline]
+  fn field(&self, acceleration: [f64; 3]) -> [f64; 3] {
+    acceleration
+  }
+}
+
+/// Newton, interaction ignorée en deçà de `MIN_DISTANCE`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Newtonian;
+
+impl ForceLaw for Newtonian {
+  #[inline]
+  fn pair_factor(&self, r_squared: f64, mass: f64) -> f64 {
+    if r_squared >= MIN_DISTANCE_SQUARED { mass / (r_squared * r_squared.sqrt()) } else { 0.0 }
+  }
+}
+
+/// Newton adouci de Plummer : m / (r² + ε²)^(3/2)
+#[derive(Debug, Clone, Copy)]
+pub struct Plummer {
+  softening_squared: f64,
+}
+
+impl Plummer {
+  pub fn new(softening: f64) -> Self {
+    Plummer { softening_squared: softening * softening }
+  }
+
+  /// Distance adoucie au carré, nulle pour le corps lui-même ou un voisin trop proche
+  #[inline]
+  fn softened(&self, r_squared: f64) -> f64 {
+    let s = r_squared + self.softening_squared;
+    if s >= MIN_DISTANCE_SQUARED { s } else { 0.0 }
+  }
+}
+
+impl ForceLaw for Plummer {
+  #[inline]
+  fn pair_factor(&self, r_squared: f64, mass: f64) -> f64 {
+    let s = self.softened(r_squared);
+    if s > 0.0 { mass / (s * s.sqrt()) } else { 0.0 }
+  }
+}
+
+/// Newton modifié par un terme de Yukawa : F = G m / r² · [1 + α (1 + r/λ) e^(−r/λ)]
+///
+/// α (`strength`) règle l'intensité de la cinquième force et λ (`range`) sa portée.
+#[derive(Debug, Clone, Copy)]
+pub struct Yukawa {
+  plummer: Plummer,
+  range: f64,
+  strength: f64,
+}
+
+impl Yukawa {
+  pub fn new(range: f64, strength: f64, softening: f64) -> Self {
+    Yukawa { plummer: Plummer::new(softening), range, strength }
+  }
+}
+
+impl ForceLaw for Yukawa {
+  #[inline]
+  fn pair_factor(&self, r_squared: f64, mass: f64) -> f64 {
+    let s = self.plummer.softened(r_squared);
+    if s <= 0.0 {
+      return 0.0;
+    }
+    let x = s.sqrt() / self.range;
+    mass * (1.0 + self.strength * (1.0 + x) * (-x).exp()) / (s * s.sqrt())
+  }
+}
+
+/// Dynamique newtonienne modifiée (MOND) avec la fonction d'interpolation « simple »
+///
+/// Le champ newtonien total g_N est amplifié en g = ν(|g_N| / a₀) · g_N avec
+/// ν(y) = 1/2 + √(1/4 + 1/y) : newtonien pour g_N ≫ a₀, en √(a₀ g_N) pour g_N ≪ a₀.
+/// Il s'agit d'une formulation de type QUMOND appliquée corps par corps, adaptée
+/// aux expériences jouets plutôt qu'à la résolution de l'équation de champ.
+#[derive(Debug, Clone, Copy)]
+pub struct Mond {
+  plummer: Plummer,
+  acceleration_scale: f64,
+}
+
+impl Mond {
+  pub fn new(acceleration_scale: f64, softening: f64) -> Self {
+    Mond { plummer: Plummer::new(softening), acceleration_scale }
+  }
+}
+
+impl ForceLaw for Mond {
+  #[inline]
+  fn pair_factor(&self, r_squared: f64, mass: f64) -> f64 {
+    self.plummer.pair_factor(r_squared, mass)
+  }
+
+  fn field(&self, acceleration: [f64; 3]) -> [f64; 3] {
+    let g = acceleration.iter().map(|a| a * a).sum::<f64>().sqrt();
+    if g == 0.0 {
+      return acceleration;
+    }
+    let y = g / self.acceleration_scale;
+    let nu = 0.5 + (0.25 + 1.0 / y).sqrt();
+    acceleration.map(|a| nu * a)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MASS: f64 = 5.97e24;
+
+  fn relative(a: f64, b: f64) -> f64 {
+    (a - b).abs() / b.abs()
+  }
+
+  #[test]
+  fn modified_laws_reduce_to_newton_in_their_limits() {
+    let newton = |r: f64| Newtonian.pair_factor(r * r, MASS);
+    for r in [1e4, 6.4e6, 1.5e11] {
+      // Plummer : adoucissement nul, ou négligeable devant la distance
+      assert_eq!(Plummer::new(0.0).pair_factor(r * r, MASS), newton(r));
+      assert!(relative(Plummer::new(1e-3 * r).pair_factor(r * r, MASS), newton(r)) < 2e-6);
+      // Yukawa : intensité nulle, ou portée négligeable devant la distance
+      assert_eq!(Yukawa::new(1e5, 0.0, 0.0).pair_factor(r * r, MASS), newton(r));
+      assert!(relative(Yukawa::new(1e-3 * r, 0.5, 0.0).pair_factor(r * r, MASS), newton(r)) < 1e-12);
+      // Portée très supérieure à la distance : G est multipliée par 1 + α
+      assert!(relative(Yukawa::new(1e6 * r, 0.5, 0.0).pair_factor(r * r, MASS), 1.5 * newton(r)) < 1e-6);
+    }
+    // L'adoucissement borne l'attraction au centre, que Newton ignore en deçà de MIN_DISTANCE
+    assert_eq!(Newtonian.pair_factor(0.0, MASS), 0.0);
+    assert_eq!(Plummer::new(1e5).pair_factor(0.0, MASS), MASS / 1e15);
+  }
+
+  #[test]
+  fn mond_is_newtonian_at_high_acceleration_and_deep_below_a0() {
+    let mond = Mond::new(1.2e-10, 0.0);
+    let norm = |a: [f64; 3]| a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    // Champ terrestre au sol : a ≫ a₀, l'écart relatif vaut environ a₀ / a
+    let strong = [0.0, 6.0, 8.0];
+    assert!(relative(norm(mond.field(strong)), 10.0) < 2e-11);
+    assert!(mond.field(strong).iter().zip(strong).all(|(m, n)| relative(*m, n) < 2e-11 || n == 0.0));
+    // Régime profond : |g| tend vers √(a₀ g_N)
+    let weak = 1e-16;
+    assert!(relative(norm(mond.field([weak, 0.0, 0.0])), (1.2e-10 * weak).sqrt()) < 1e-3);
+    assert_eq!(mond.field([0.0; 3]), [0.0; 3]);
+  }
+}
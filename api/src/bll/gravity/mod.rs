@@ -0,0 +1,41 @@
+pub mod barnes_hut;
+pub mod direct;
+pub mod force_law;
+pub mod relativity;
+
+use std::sync::Arc;
+
+use crate::bll::gravity::force_law::{ForceLaw, Mond, Newtonian, Plummer, Yukawa};
+use crate::bll::state::Vectors;
+use crate::bo::simulation_options::{ForceLawKind, ForceSolverKind, SimulationOptions};
+
+pub use crate::bo::utils::G;
+
+/// En deçà de cette distance (m, adoucissement compris), l'interaction entre deux corps est ignorée
+pub const MIN_DISTANCE: f64 = 1e3;
+
+/// Interface commune des méthodes de calcul des forces gravitationnelles
+pub trait ForceSolver: Send + Sync {
+  /// Écrit dans `out` l'accélération gravitationnelle subie par chaque corps aux positions données
+  ///
+  /// Seuls les `masses.len()` premiers corps sont sources ; les suivants, des particules
+  /// test, subissent le champ sans l'exercer, d'où un coût en O(N·M).
+  fn accelerations(&self, positions: &Vectors, masses: &[f64], out: &mut Vectors);
+}
+
+/// Instancie la méthode de calcul des forces et la loi d'interaction choisies par la requête
+pub fn build(options: &SimulationOptions) -> Arc<dyn ForceSolver> {
+  match options.force_law {
+    ForceLawKind::Newtonian => with_law(options, Newtonian),
+    ForceLawKind::Softened { softening } => with_law(options, Plummer::new(softening)),
+    ForceLawKind::Yukawa { range, strength, softening } => with_law(options, Yukawa::new(range, strength, softening)),
+    ForceLawKind::Mond { acceleration_scale, softening } => with_law(options, Mond::new(acceleration_scale, softening)),
+  }
+}
+
+fn with_law<L: ForceLaw>(options: &SimulationOptions, law: L) -> Arc<dyn ForceSolver> {
+  match options.force_solver {
+    ForceSolverKind::Direct => Arc::new(direct::DirectSummation::new(law)),
+    ForceSolverKind::BarnesHut => Arc::new(barnes_hut::BarnesHut::new(options.opening_angle, law)),
+  }
+}
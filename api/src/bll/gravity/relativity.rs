@@ -0,0 +1,137 @@
+use crate::bll::forces::{dominant, Force};
+use crate::bll::gravity::{G, MIN_DISTANCE};
+use crate::bll::state::Vectors;
+use crate::bo::force_parameters::ForceParameters;
+
+/// Vitesse de la lumière dans le vide (m/s)
+pub const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// Correction post-newtonienne (1PN) due au corps dominant
+///
+/// Chaque corps subit, relativement au plus massif, le terme de Schwarzschild
+/// a = GM / (c² r³) · [(4GM/r − v²) r + 4 (r·v) v], dont la réaction (× m/M) est
+/// reportée sur le corps dominant. Ce terme suffit à l'avance des périhélies
+/// planétaires ; les termes croisés entre corps secondaires du développement
+/// d'Einstein–Infeld–Hoffmann sont négligés.
+pub struct PostNewtonian;
+
+impl Force for PostNewtonian {
+  fn add_accelerations(
+    &self,
+    positions: &Vectors,
+    velocities: &Vectors,
+    masses: &[f64],
+    _parameters: &[ForceParameters],
+    out: &mut Vectors,
+  ) {
+    add_post_newtonian(positions, velocities, masses, out);
+  }
+
+  fn depends_on_velocity(&self) -> bool {
+    true
+  }
+}
+
+fn add_post_newtonian(positions: &Vectors, velocities: &Vectors, masses: &[f64], out: &mut Vectors) {
+  let Some(central) = dominant(masses) else {
+    return;
+  };
+  let mu = G * masses[central];
+  let c_squared = SPEED_OF_LIGHT * SPEED_OF_LIGHT;
+  let (pc, vc) = (positions.get(central), velocities.get(central));
+
+  let mut reaction = [0.0; 3];
+  for i in (0..masses.len()).filter(|&i| i != central) {
+    let (pi, vi) = (positions.get(i), velocities.get(i));
+    let r: [f64; 3] = std::array::from_fn(|k| pi[k] - pc[k]);
+    let v: [f64; 3] = std::array::from_fn(|k| vi[k] - vc[k]);
+    let r_squared = r[0] * r[0] + r[1] * r[1] + r[2] * r[2];
+    if r_squared < MIN_DISTANCE * MIN_DISTANCE {
+      continue;
+    }
+    let distance = r_squared.sqrt();
+    let v_squared = v[0] * v[0] + v[1] * v[1] + v[2] * v[2];
+    let r_dot_v = r[0] * v[0] + r[1] * v[1] + r[2] * v[2];
+
+    let factor = mu / (c_squared * r_squared * distance);
+    let radial = 4.0 * mu / distance - v_squared;
+    let correction: [f64; 3] = std::array::from_fn(|k| factor * (radial * r[k] + 4.0 * r_dot_v * v[k]));
+
+    let acceleration = out.get(i);
+    out.set(i, std::array::from_fn(|k| acceleration[k] + correction[k]));
+    for k in 0..3 {
+      reaction[k] -= masses[i] / masses[central] * correction[k];
+    }
+  }
+  let acceleration = out.get(central);
+  out.set(central, std::array::from_fn(|k| acceleration[k] + reaction[k]));
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bll::integrator;
+  use crate::bll::state::SystemState;
+  use crate::bo::celest_item::CelestItem;
+  use crate::bo::simulation_options::{IntegratorKind, SimulationOptions};
+
+  const SUN_MASS: f64 = 1.98847e30;
+  const MERCURY_MASS: f64 = 3.3011e23;
+  const YEAR: f64 = 365.25 * 86400.0;
+
+  fn body(name: &str, mass: f64, position: [f64; 3], velocity: [f64; 3]) -> CelestItem {
+    CelestItem { name: name.into(), mass, radius: 1.0, position, velocity, ..Default::default() }
+  }
+
+  /// Argument du périhélie de Mercure (rad), tiré du vecteur de Laplace–Runge–Lenz
+  fn perihelion_longitude(state: &SystemState) -> f64 {
+    let (ps, pm) = (state.positions.get(0), state.positions.get(1));
+    let (vs, vm) = (state.velocities.get(0), state.velocities.get(1));
+    let r: [f64; 3] = std::array::from_fn(|k| pm[k] - ps[k]);
+    let v: [f64; 3] = std::array::from_fn(|k| vm[k] - vs[k]);
+    let mu = G * (SUN_MASS + MERCURY_MASS);
+    let h = r[0] * v[1] - r[1] * v[0];
+    let distance = (r[0] * r[0] + r[1] * r[1]).sqrt();
+    let lrl = [v[1] * h - mu * r[0] / distance, -v[0] * h - mu * r[1] / distance];
+    lrl[1].atan2(lrl[0])
+  }
+
+  /// Avance du périhélie de Mercure sur un siècle, en secondes d'arc
+  fn perihelion_advance(post_newtonian: bool) -> f64 {
+    // Mercure au périhélie, a = 0,387 UA et e = 0,2056, dans le repère du barycentre
+    let (a, e) = (5.790_905e10, 0.205_63);
+    let mu = G * (SUN_MASS + MERCURY_MASS);
+    let (r, v) = (a * (1.0 - e), (mu * (1.0 + e) / (a * (1.0 - e))).sqrt());
+    let share = MERCURY_MASS / (SUN_MASS + MERCURY_MASS);
+    let items = vec![
+      body("Soleil", SUN_MASS, [-share * r, 0.0, 0.0], [0.0, -share * v, 0.0]),
+      body("Mercure", MERCURY_MASS, [(1.0 - share) * r, 0.0, 0.0], [0.0, (1.0 - share) * v, 0.0]),
+    ];
+
+    let options = SimulationOptions { integrator: IntegratorKind::Ias15, post_newtonian, ..Default::default() };
+    let mut state = SystemState::from_items(&items);
+    let mut integrator = integrator::build(&options, &state);
+    let start = perihelion_longitude(&state);
+    let duration = 100.0 * YEAR;
+    let mut elapsed = 0.0;
+    while elapsed < duration {
+      elapsed += integrator.step(&mut state, duration - elapsed).unwrap();
+    }
+    let advance = (perihelion_longitude(&state) - start).rem_euclid(std::f64::consts::TAU);
+    let advance = if advance > std::f64::consts::PI { advance - std::f64::consts::TAU } else { advance };
+    advance.to_degrees() * 3600.0
+  }
+
+  #[test]
+  fn mercury_perihelion_advances_43_arcseconds_per_century() {
+    let advance = perihelion_advance(true);
+    assert!((advance - 42.98).abs() < 0.5, "avance relativiste : {advance}″/siècle");
+  }
+
+  #[test]
+  fn newtonian_orbit_does_not_precess() {
+    let advance = perihelion_advance(false);
+    assert!(advance.abs() < 0.05, "avance newtonienne : {advance}″/siècle");
+  }
+}
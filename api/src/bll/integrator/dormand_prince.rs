@@ -0,0 +1,221 @@
+use anyhow::Result;
+
+use crate::bll::dynamics::Dynamics;
+use crate::bll::integrator::Integrator;
+use crate::bll::state::{SystemState, Vectors};
+use crate::bo::simulation_options::Tolerance;
+
+// Tableau de Butcher de Dormand–Prince 5(4) (système autonome : les nœuds c_i sont inutiles)
+const A: [[f64; 6]; 7] = [
+  [0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+  [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+  [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+  [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+  [19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0, 0.0, 0.0],
+  [9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0, 0.0],
+  [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+// Différence entre les solutions d'ordre 5 et d'ordre 4 (estimation de l'erreur locale)
+const E: [f64; 7] = [
+  71.0 / 57600.0,
+  0.0,
+  -71.0 / 16695.0,
+  71.0 / 1920.0,
+  -17253.0 / 339200.0,
+  22.0 / 525.0,
+  -1.0 / 40.0,
+];
+
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 5.0;
+// En deçà, le pas est accepté malgré l'erreur pour ne pas boucler indéfiniment
+const MIN_STEP: f64 = 1e-6;
+
+/// Dérivée de l'état à une étape : vitesses et accélérations
+#[derive(Default)]
+struct Stage {
+  velocities: Vectors,
+  accelerations: Vectors,
+}
+
+/// Runge–Kutta emboîté de Dormand–Prince 5(4) à pas adaptatif
+///
+/// Le pas est choisi pour que l'erreur locale estimée reste sous
+/// `absolute + relative * |y|` sur chaque composante de position (m) et de vitesse (m/s).
+/// Les sept étapes et l'état intermédiaire occupent des tampons alloués une fois pour toutes.
+pub struct DormandPrince {
+  dynamics: Dynamics,
+  tolerance: Tolerance,
+  h: Option<f64>,
+  stages: Vec<Stage>,
+  positions: Vectors,
+  velocities: Vectors,
+  position_errors: Vectors,
+  velocity_errors: Vectors,
+  // La dernière étape d'un pas accepté est la première du suivant (FSAL)
+  fsal: bool,
+  rejected: u64,
+}
+
+impl DormandPrince {
+  pub fn new(tolerance: Tolerance, dynamics: Dynamics) -> Self {
+    DormandPrince {
+      dynamics,
+      tolerance,
+      h: None,
+      stages: (0..7).map(|_| Stage::default()).collect(),
+      positions: Vectors::default(),
+      velocities: Vectors::default(),
+      position_errors: Vectors::default(),
+      velocity_errors: Vectors::default(),
+      fsal: false,
+      rejected: 0,
+    }
+  }
+
+  fn evaluate(dynamics: &Dynamics, state: &SystemState, positions: &Vectors, velocities: &Vectors, stage: &mut Stage) {
+    stage.velocities.copy_from(velocities);
+    dynamics.accelerations(positions, velocities, &state.masses, &state.force_parameters, &mut stage.accelerations);
+    state.pin(&mut stage.accelerations);
+  }
+
+  /// Norme RMS pondérée par la tolérance, rapportée aux seules composantes des objets mobiles
+  fn rms(&self, state: &SystemState, terms: impl Iterator<Item = (f64, f64)>) -> f64 {
+    let count = 6 * state.mobile_count();
+    let sum: f64 = terms
+      .map(|(value, magnitude)| (value / (self.tolerance.absolute + self.tolerance.relative * magnitude)).powi(2))
+      .sum();
+    if count == 0 { 0.0 } else { (sum / count as f64).sqrt() }
+  }
+
+  /// Pas initial selon Hairer, Nørsett & Wanner (Solving ODE I, II.4)
+  fn initial_step(&mut self, state: &SystemState) -> f64 {
+    let y0 = || state.positions.as_slice().iter().chain(state.velocities.as_slice());
+    let f0 = |stage: &Stage| {
+      stage.velocities.as_slice().iter().chain(stage.accelerations.as_slice()).copied().collect::<Vec<f64>>()
+    };
+    let f0 = f0(&self.stages[0]);
+
+    let d0 = self.rms(state, y0().map(|y| (*y, y.abs())));
+    let d1 = self.rms(state, f0.iter().zip(y0()).map(|(f, y)| (*f, y.abs())));
+    let h0 = if d0 < 1e-5 || d1 < 1e-5 { 1e-6 } else { 0.01 * d0 / d1 };
+
+    self.positions.copy_from(&state.positions);
+    self.positions.add_scaled(&self.stages[0].velocities, h0);
+    self.velocities.copy_from(&state.velocities);
+    self.velocities.add_scaled(&self.stages[0].accelerations, h0);
+    let (first, rest) = self.stages.split_at_mut(1);
+    Self::evaluate(&self.dynamics, state, &self.positions, &self.velocities, &mut rest[0]);
+    let f1 = rest[0].velocities.as_slice().iter().chain(rest[0].accelerations.as_slice());
+    let f0 = first[0].velocities.as_slice().iter().chain(first[0].accelerations.as_slice());
+    let df: Vec<f64> = f1.zip(f0).map(|(a, b)| a - b).collect();
+    let d2 = self.rms(state, df.iter().zip(y0()).map(|(d, y)| (*d, y.abs()))) / h0;
+
+    let h1 = if d1.max(d2) <= 1e-15 {
+      (h0 * 1e-3).max(1e-6)
+    } else {
+      (0.01 / d1.max(d2)).powf(1.0 / 5.0)
+    };
+    (100.0 * h0).min(h1)
+  }
+}
+
+impl Integrator for DormandPrince {
+  fn step(&mut self, state: &mut SystemState, dt: f64) -> Result<f64> {
+    if !self.fsal || self.stages[0].accelerations.len() != state.len() {
+      Self::evaluate(&self.dynamics, state, &state.positions, &state.velocities, &mut self.stages[0]);
+    }
+
+    let direction = dt.signum();
+    let mut h = match self.h {
+      Some(h) => h,
+      None => self.initial_step(state),
+    };
+
+    loop {
+      let step = direction * h.min(dt.abs());
+
+      // Étapes k2..k7 (k7 évaluée en y5, réutilisée au pas suivant)
+      for (s, a) in A.iter().enumerate().skip(1) {
+        self.positions.copy_from(&state.positions);
+        self.velocities.copy_from(&state.velocities);
+        let (done, rest) = self.stages.split_at_mut(s);
+        for (stage, coefficient) in done.iter().zip(a).filter(|(_, c)| **c != 0.0) {
+          self.positions.add_scaled(&stage.velocities, step * coefficient);
+          self.velocities.add_scaled(&stage.accelerations, step * coefficient);
+        }
+        Self::evaluate(&self.dynamics, state, &self.positions, &self.velocities, &mut rest[0]);
+      }
+
+      self.position_errors.resize(state.len());
+      self.velocity_errors.resize(state.len());
+      self.position_errors.fill(0.0);
+      self.velocity_errors.fill(0.0);
+      for (stage, e) in self.stages.iter().zip(E).filter(|(_, e)| *e != 0.0) {
+        self.position_errors.add_scaled(&stage.velocities, step * e);
+        self.velocity_errors.add_scaled(&stage.accelerations, step * e);
+      }
+      let errors = self.position_errors.as_slice().iter().chain(self.velocity_errors.as_slice());
+      let y0 = state.positions.as_slice().iter().chain(state.velocities.as_slice());
+      let y1 = self.positions.as_slice().iter().chain(self.velocities.as_slice());
+      let err_norm = self.rms(state, errors.zip(y0.zip(y1)).map(|(e, (a, b))| (*e, a.abs().max(b.abs()))));
+
+      if err_norm <= 1.0 || step.abs() <= MIN_STEP {
+        let factor = if err_norm == 0.0 { MAX_FACTOR } else { SAFETY * err_norm.powf(-1.0 / 5.0) };
+        self.h = Some(h * factor.clamp(MIN_FACTOR, MAX_FACTOR));
+        std::mem::swap(&mut state.positions, &mut self.positions);
+        std::mem::swap(&mut state.velocities, &mut self.velocities);
+        self.stages.swap(0, 6);
+        self.fsal = true;
+        return Ok(step);
+      }
+
+      self.rejected += 1;
+      let factor = SAFETY * err_norm.powf(-1.0 / 5.0);
+      h = step.abs() * factor.clamp(MIN_FACTOR, 1.0);
+    }
+  }
+
+  fn reset(&mut self) {
+    self.fsal = false;
+  }
+
+  fn rejected_steps(&self) -> u64 {
+    self.rejected
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::bll::integrator::fixtures::*;
+  use crate::bo::simulation_options::{IntegratorKind, SimulationOptions, Tolerance};
+
+  #[test]
+  fn error_control_rejects_steps_and_meets_the_tolerance() {
+    let items = orbit(0.9);
+    let errors: Vec<(f64, u64, u64)> = [1e-8, 1e-11]
+      .into_iter()
+      .map(|relative| {
+        let options = SimulationOptions {
+          integrator: IntegratorKind::DormandPrince,
+          tolerance: Tolerance { relative, absolute: 1e-6 },
+          ..Default::default()
+        };
+        // Pas demandé d'une période entière : seul le contrôle d'erreur le limite
+        let (end, accepted, rejected) = integrate(&options, &items, period(), period());
+        (distance(&end[1], &items[1]) / SEMI_MAJOR_AXIS, accepted, rejected)
+      })
+      .collect();
+
+    for (error, _, rejected) in &errors {
+      assert!(*rejected > 0, "aucun pas rejeté");
+      assert!(*error < 1e-3, "écart au périhélie {error}");
+    }
+    // Une tolérance plus stricte coûte davantage de pas et réduit l'écart
+    let ((loose, loose_steps, _), (tight, tight_steps, _)) = (errors[0], errors[1]);
+    assert!(tight_steps > 2 * loose_steps, "{loose_steps} puis {tight_steps} pas");
+    assert!(tight < loose / 10.0, "{loose} puis {tight}");
+  }
+}
@@ -0,0 +1,166 @@
+use anyhow::Result;
+
+use crate::bll::dynamics::Dynamics;
+use crate::bll::integrator::Integrator;
+use crate::bll::state::{SystemState, Vectors};
+
+/// Euler semi-implicite (symplectique d'ordre 1), schéma historique du simulateur
+pub struct Euler {
+  dynamics: Dynamics,
+  accelerations: Vectors,
+}
+
+impl Euler {
+  pub fn new(dynamics: Dynamics) -> Self {
+    Euler { dynamics, accelerations: Vectors::default() }
+  }
+}
+
+impl Integrator for Euler {
+  fn step(&mut self, state: &mut SystemState, dt: f64) -> Result<f64> {
+    self.dynamics.accelerations(&state.positions, &state.velocities, &state.masses, &state.force_parameters, &mut self.accelerations);
+    state.pin(&mut self.accelerations);
+
+    state.velocities.add_scaled(&self.accelerations, dt);
+    state.positions.add_scaled(&state.velocities, dt);
+    Ok(dt)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::Arc;
+  use std::time::{Duration, Instant};
+
+  use rayon::prelude::*;
+
+  use crate::bll::gravity::direct::DirectSummation;
+  use crate::bll::gravity::force_law::Newtonian;
+  use crate::bll::gravity::{G, MIN_DISTANCE};
+  use crate::bo::celest_item::CelestItem;
+
+  /// Disque pseudo-aléatoire reproductible de `n` corps en orbite autour d'un Soleil fixe
+  fn system(n: usize) -> Vec<CelestItem> {
+    let mut seed: u64 = 7;
+    let mut next = || {
+      seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+      (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let item = |name: String, mass: f64, position: [f64; 3], velocity: [f64; 3]| CelestItem {
+      fixed: name == "Soleil",
+      name,
+      mass,
+      radius: 1e6,
+      position,
+      velocity,
+      ..Default::default()
+    };
+    let mut items = vec![item("Soleil".into(), 1.989e30, [0.0; 3], [0.0; 3])];
+    for i in 1..n {
+      let r = 1.496e11 * (0.5 + 4.5 * next());
+      let angle = std::f64::consts::TAU * next();
+      let v = (G * 1.989e30 / r).sqrt();
+      items.push(item(
+        format!("Corps {i}"),
+        1e20 * (1.0 + 9.0 * next()),
+        [r * angle.cos(), r * angle.sin(), 0.0],
+        [-v * angle.sin(), v * angle.cos(), 0.0],
+      ));
+    }
+    items
+  }
+
+  /// Boucle historique : clone de l'état dans un `Arc` et allocation des accélérations à chaque pas
+  fn legacy_step(state: &mut Vec<CelestItem>, dt: f64) {
+    let shared_state = Arc::new(state.clone());
+    let accelerations: Vec<[f64; 3]> = (0..shared_state.len())
+      .into_par_iter()
+      .map(|i| {
+        let mut acc = [0.0; 3];
+        for (j, other) in shared_state.iter().enumerate() {
+          if i == j {
+            continue;
+          }
+          let self_p = &shared_state[i];
+          let d: [f64; 3] = std::array::from_fn(|k| other.position[k] - self_p.position[k]);
+          let r_squared = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+          let r = r_squared.sqrt();
+          if r < MIN_DISTANCE {
+            continue;
+          }
+          let force_mag = G * other.mass / r_squared;
+          for (a, d) in acc.iter_mut().zip(d) {
+            *a += force_mag * d / r;
+          }
+        }
+        acc
+      })
+      .collect();
+    state.par_iter_mut().zip(accelerations.par_iter()).for_each(|(item, acc)| {
+      if item.name != "Soleil" {
+        for ((v, p), a) in item.velocity.iter_mut().zip(item.position.iter_mut()).zip(acc) {
+          *v += a * dt;
+          *p += *v * dt;
+        }
+      }
+    });
+  }
+
+  fn time(steps: usize, mut step: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..steps {
+      step();
+    }
+    start.elapsed() / steps as u32
+  }
+
+  #[test]
+  fn matches_legacy_loop() {
+    let items = system(50);
+    let mut legacy = items.clone();
+    let mut state = SystemState::from_items(&items);
+    let mut euler = Euler::new(Dynamics::new(Arc::new(DirectSummation::new(Newtonian))));
+    for _ in 0..100 {
+      legacy_step(&mut legacy, 3600.0);
+      euler.step(&mut state, 3600.0).unwrap();
+    }
+    let mut result = items.clone();
+    state.write_to(&mut result);
+    for (a, b) in legacy.iter().zip(&result) {
+      for k in 0..3 {
+        assert!((a.position[k] - b.position[k]).abs() <= 1e-9 * a.position[k].abs().max(1.0));
+      }
+    }
+  }
+
+  /// Débit par pas de la boucle historique et du noyau en structure de tableaux, sur un seul
+  /// fil de rayon pour comparer les deux à nombre de cœurs égal
+  ///
+  /// `cargo test --release throughput -- --ignored --nocapture`
+  #[test]
+  #[ignore]
+  fn throughput_against_legacy_loop() {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    for (n, steps) in [(9, 20_000), (100, 2_000), (1_000, 50), (4_000, 5)] {
+      let items = system(n);
+      let (legacy_time, soa_time) = pool.install(|| {
+        let mut legacy = items.clone();
+        let legacy_time = time(steps, || legacy_step(&mut legacy, 3600.0));
+
+        let mut state = SystemState::from_items(&items);
+        let mut euler = Euler::new(Dynamics::new(Arc::new(DirectSummation::new(Newtonian))));
+        let soa_time = time(steps, || {
+          euler.step(&mut state, 3600.0).unwrap();
+        });
+        (legacy_time, soa_time)
+      });
+
+      println!(
+        "N = {n:>5} : historique {legacy_time:>12.3?}/pas, structure de tableaux {soa_time:>12.3?}/pas (x{:.1})",
+        legacy_time.as_secs_f64() / soa_time.as_secs_f64()
+      );
+    }
+  }
+}
@@ -0,0 +1,407 @@
+// Noyau numérique indexé composante par composante, plus lisible avec des boucles explicites
+#![allow(clippy::needless_range_loop)]
+
+use anyhow::Result;
+
+use crate::bll::dynamics::Dynamics;
+use crate::bll::integrator::Integrator;
+use crate::bll::state::{SystemState, Vectors};
+
+// Espacements de Gauss–Radau sur [0, 1]
+const H: [f64; 8] = [
+  0.0,
+  0.056_262_560_536_922_15,
+  0.180_240_691_736_892_36,
+  0.352_624_717_113_169_6,
+  0.547_153_626_330_555_4,
+  0.734_210_177_215_410_5,
+  0.885_320_946_839_095_8,
+  0.977_520_613_561_287_5,
+];
+
+// Précision visée sur le dernier coefficient du développement (Rein & Spiegel 2015)
+const EPSILON: f64 = 1e-9;
+const SAFETY_FACTOR: f64 = 0.25;
+const MAX_ITERATIONS: usize = 12;
+const MIN_STEP: f64 = 1e-6;
+
+/// Coefficients du développement de l'accélération sur le pas, un vecteur de 3N par ordre
+type Coefficients = [Vec<f64>; 7];
+
+/// Addition compensée de Kahan
+fn add_compensated(sum: &mut f64, compensation: &mut f64, input: f64) {
+  let y = input - *compensation;
+  let t = *sum + y;
+  *compensation = (t - *sum) - y;
+  *sum = t;
+}
+
+/// Intégrateur de Gauss–Radau d'ordre 15 à pas adaptatif, dans l'esprit d'IAS15
+///
+/// L'accélération sur le pas est développée en polynôme de degré 7 dont les
+/// coefficients sont obtenus par prédicteur-correcteur aux sous-pas de Radau.
+/// Le pas est choisi pour que le dernier coefficient reste négligeable devant
+/// l'accélération, ce qui ramène l'erreur au niveau de l'arrondi machine et
+/// resserre automatiquement le pas lors des rencontres proches.
+pub struct Ias15 {
+  dynamics: Dynamics,
+  rr: [f64; 28],
+  c: [f64; 21],
+  d: [f64; 21],
+  dt: Option<f64>,
+  dt_last_success: f64,
+  // État interne en double précision compensée, resynchronisé si l'état a changé entre deux pas
+  x0: Vectors,
+  v0: Vectors,
+  masses: Vec<f64>,
+  csx: Vec<f64>,
+  csv: Vec<f64>,
+  b: Coefficients,
+  e: Coefficients,
+  br: Coefficients,
+  er: Coefficients,
+  g: Coefficients,
+  // Tampons d'évaluation réutilisés d'un pas à l'autre
+  a0: Vectors,
+  at: Vectors,
+  positions: Vectors,
+  velocities: Vectors,
+  rejected: u64,
+}
+
+impl Ias15 {
+  pub fn new(dynamics: Dynamics) -> Self {
+    let mut rr = [0.0; 28];
+    let mut l = 0;
+    for n in 1..8 {
+      for j in 0..n {
+        rr[l] = H[n] - H[j];
+        l += 1;
+      }
+    }
+
+    let mut c = [0.0; 21];
+    let mut d = [0.0; 21];
+    c[0] = -H[1];
+    d[0] = H[1];
+    let mut l = 0;
+    for j in 2..7 {
+      l += 1;
+      c[l] = -H[j] * c[l + 1 - j];
+      d[l] = H[1] * d[l + 1 - j];
+      for k in 2..j {
+        l += 1;
+        c[l] = c[l - j] - H[j] * c[l + 1 - j];
+        d[l] = d[l - j] + H[k] * d[l + 1 - j];
+      }
+      l += 1;
+      c[l] = c[l - j] - H[j];
+      d[l] = d[l - j] + H[j];
+    }
+
+    Ias15 {
+      dynamics,
+      rr,
+      c,
+      d,
+      dt: None,
+      dt_last_success: 0.0,
+      x0: Vectors::default(),
+      v0: Vectors::default(),
+      masses: Vec::new(),
+      csx: Vec::new(),
+      csv: Vec::new(),
+      b: Default::default(),
+      e: Default::default(),
+      br: Default::default(),
+      er: Default::default(),
+      g: Default::default(),
+      a0: Vectors::default(),
+      at: Vectors::default(),
+      positions: Vectors::default(),
+      velocities: Vectors::default(),
+      rejected: 0,
+    }
+  }
+
+  /// Recharge l'état interne depuis `state` si celui-ci a été modifié hors de l'intégrateur
+  fn sync(&mut self, state: &SystemState) {
+    let unchanged = self.x0 == state.positions && self.v0 == state.velocities && self.masses == state.masses;
+    if unchanged {
+      return;
+    }
+
+    let n3 = 3 * state.len();
+    self.x0.copy_from(&state.positions);
+    self.v0.copy_from(&state.velocities);
+    self.masses.clone_from(&state.masses);
+    self.csx = vec![0.0; n3];
+    self.csv = vec![0.0; n3];
+    for coefficients in [&mut self.b, &mut self.e, &mut self.br, &mut self.er, &mut self.g] {
+      for p in coefficients.iter_mut() {
+        *p = vec![0.0; n3];
+      }
+    }
+    self.a0.resize(state.len());
+    self.at.resize(state.len());
+    self.positions.resize(state.len());
+    self.velocities.resize(state.len());
+    self.dt_last_success = 0.0;
+  }
+
+  fn accelerations(
+    dynamics: &Dynamics,
+    state: &SystemState,
+    positions: &Vectors,
+    velocities: &Vectors,
+    out: &mut Vectors,
+  ) {
+    dynamics.accelerations(positions, velocities, &state.masses, &state.force_parameters, out);
+    state.pin(out);
+  }
+
+  /// Pas initial : un centième du plus court temps caractéristique |v| / |a|
+  fn initial_step(&self, dt_max: f64) -> f64 {
+    let mut dt = dt_max.abs();
+    for i in 0..self.v0.len() {
+      let v_norm = self.v0.get(i).iter().map(|x| x * x).sum::<f64>().sqrt();
+      let a_norm = self.a0.get(i).iter().map(|x| x * x).sum::<f64>().sqrt();
+      if a_norm > 0.0 && v_norm > 0.0 {
+        dt = dt.min(0.01 * v_norm / a_norm);
+      }
+    }
+    dt.max(MIN_STEP)
+  }
+
+  /// Extrapole les coefficients du pas courant vers le pas suivant de rapport `ratio`
+  fn predict_next_step(&mut self, ratio: f64) {
+    if ratio > 20.0 {
+      // Trop d'écart avec le pas précédent : la prédiction n'aurait plus de sens
+      for coefficients in [&mut self.b, &mut self.e] {
+        for p in coefficients.iter_mut() {
+          p.iter_mut().for_each(|x| *x = 0.0);
+        }
+      }
+      return;
+    }
+
+    let q1 = ratio;
+    let q2 = q1 * q1;
+    let q3 = q1 * q2;
+    let q4 = q2 * q2;
+    let q5 = q2 * q3;
+    let q6 = q3 * q3;
+    let q7 = q3 * q4;
+
+    for k in 0..self.b[0].len() {
+      let b: [f64; 7] = std::array::from_fn(|i| self.br[i][k]);
+      let be: [f64; 7] = std::array::from_fn(|i| self.br[i][k] - self.er[i][k]);
+
+      let e = [
+        q1 * (b[6] * 7.0 + b[5] * 6.0 + b[4] * 5.0 + b[3] * 4.0 + b[2] * 3.0 + b[1] * 2.0 + b[0]),
+        q2 * (b[6] * 21.0 + b[5] * 15.0 + b[4] * 10.0 + b[3] * 6.0 + b[2] * 3.0 + b[1]),
+        q3 * (b[6] * 35.0 + b[5] * 20.0 + b[4] * 10.0 + b[3] * 4.0 + b[2]),
+        q4 * (b[6] * 35.0 + b[5] * 15.0 + b[4] * 5.0 + b[3]),
+        q5 * (b[6] * 21.0 + b[5] * 6.0 + b[4]),
+        q6 * (b[6] * 7.0 + b[5]),
+        q7 * b[6],
+      ];
+      for i in 0..7 {
+        self.e[i][k] = e[i];
+        self.b[i][k] = e[i] + be[i];
+      }
+    }
+  }
+
+}
+
+impl Integrator for Ias15 {
+  fn step(&mut self, state: &mut SystemState, dt: f64) -> Result<f64> {
+    self.sync(state);
+    let n3 = 3 * state.len();
+    let direction = dt.signum();
+
+    Self::accelerations(&self.dynamics, state, &self.x0, &self.v0, &mut self.a0);
+    let mut step_size = match self.dt {
+      Some(h) => h,
+      None => self.initial_step(dt),
+    };
+
+    loop {
+      let dt_done = direction * step_size.min(dt.abs());
+
+      // Différences divisées g déduites des coefficients b prédits
+      let d = &self.d;
+      let b = &self.b;
+      let g = &mut self.g;
+      for k in 0..n3 {
+        g[0][k] = b[6][k] * d[15] + b[5][k] * d[10] + b[4][k] * d[6] + b[3][k] * d[3] + b[2][k] * d[1] + b[1][k] * d[0] + b[0][k];
+        g[1][k] = b[6][k] * d[16] + b[5][k] * d[11] + b[4][k] * d[7] + b[3][k] * d[4] + b[2][k] * d[2] + b[1][k];
+        g[2][k] = b[6][k] * d[17] + b[5][k] * d[12] + b[4][k] * d[8] + b[3][k] * d[5] + b[2][k];
+        g[3][k] = b[6][k] * d[18] + b[5][k] * d[13] + b[4][k] * d[9] + b[3][k];
+        g[4][k] = b[6][k] * d[19] + b[5][k] * d[14] + b[4][k];
+        g[5][k] = b[6][k] * d[20] + b[5][k];
+        g[6][k] = b[6][k];
+      }
+
+      // Prédicteur-correcteur jusqu'à convergence des coefficients
+      let mut predictor_corrector_error = f64::MAX;
+      let mut previous_error = 2.0;
+      let mut iterations = 0;
+      while predictor_corrector_error >= 1e-16 {
+        if iterations > 2 && previous_error <= predictor_corrector_error {
+          break;
+        }
+        if iterations >= MAX_ITERATIONS {
+          tracing::warn!("IAS15 : le prédicteur-correcteur n'a pas convergé");
+          break;
+        }
+        previous_error = predictor_corrector_error;
+        predictor_corrector_error = 0.0;
+        iterations += 1;
+
+        for n in 1..8 {
+          let s = H[n];
+          let b = &self.b;
+          let (x0, v0, a0) = (self.x0.as_slice(), self.v0.as_slice(), self.a0.as_slice());
+          for (k, position) in self.positions.as_mut_slice().iter_mut().enumerate() {
+            let poly = ((((((b[6][k] * s / 72.0 + b[5][k] / 56.0) * s + b[4][k] / 42.0) * s + b[3][k] / 30.0) * s
+              + b[2][k] / 20.0) * s + b[1][k] / 12.0) * s + b[0][k] / 6.0) * s + a0[k] / 2.0;
+            *position = x0[k] - self.csx[k] + dt_done * s * (v0[k] + dt_done * s * poly);
+          }
+          // Vitesses prédites, seulement si les accélérations en dépendent
+          let velocity_dependent = self.dynamics.depends_on_velocity();
+          if velocity_dependent {
+            for (k, velocity) in self.velocities.as_mut_slice().iter_mut().enumerate() {
+              let poly = ((((((b[6][k] * s / 8.0 + b[5][k] / 7.0) * s + b[4][k] / 6.0) * s + b[3][k] / 5.0) * s
+                + b[2][k] / 4.0) * s + b[1][k] / 3.0) * s + b[0][k] / 2.0) * s + a0[k];
+              *velocity = v0[k] - self.csv[k] + dt_done * s * poly;
+            }
+          }
+          let velocities = if velocity_dependent { &self.velocities } else { &self.v0 };
+          Self::accelerations(&self.dynamics, state, &self.positions, velocities, &mut self.at);
+          let (at, a0, g) = (self.at.as_slice(), self.a0.as_slice(), &mut self.g);
+
+          let base = n * (n - 1) / 2;
+          let c_base = (n - 1) * n.saturating_sub(2) / 2;
+          let mut max_at: f64 = 0.0;
+          let mut max_correction: f64 = 0.0;
+          for k in 0..n3 {
+            let mut value = (at[k] - a0[k]) / self.rr[base];
+            for j in 1..n {
+              value = (value - g[j - 1][k]) / self.rr[base + j];
+            }
+            let correction = value - g[n - 1][k];
+            g[n - 1][k] = value;
+
+            for j in 0..n - 1 {
+              self.b[j][k] += correction * self.c[c_base + j];
+            }
+            self.b[n - 1][k] += correction;
+
+            if n == 7 {
+              max_at = max_at.max(at[k].abs());
+              max_correction = max_correction.max(correction.abs());
+            }
+          }
+          if n == 7 {
+            predictor_corrector_error = if max_at > 0.0 { max_correction / max_at } else { 0.0 };
+          }
+        }
+      }
+
+      // Contrôle du pas sur le dernier coefficient
+      let max_at = self.at.as_slice().iter().fold(0.0_f64, |m, x| m.max(x.abs()));
+      let max_b6 = self.b[6].iter().fold(0.0_f64, |m, x| m.max(x.abs()));
+      let integrator_error = if max_at > 0.0 { max_b6 / max_at } else { 0.0 };
+      let mut dt_new = if integrator_error.is_normal() {
+        (EPSILON / integrator_error).powf(1.0 / 7.0) * dt_done.abs()
+      } else {
+        dt_done.abs() / SAFETY_FACTOR
+      };
+      dt_new = dt_new.max(MIN_STEP);
+
+      if dt_new / dt_done.abs() < SAFETY_FACTOR && dt_done.abs() > MIN_STEP {
+        // Pas nettement trop grand : on recommence avec le nouveau pas
+        self.rejected += 1;
+        step_size = dt_new;
+        if self.dt_last_success != 0.0 {
+          self.predict_next_step(dt_new / self.dt_last_success);
+        }
+        continue;
+      }
+      dt_new = dt_new.min(dt_done.abs() / SAFETY_FACTOR);
+
+      // Pas accepté : mise à jour compensée des positions et vitesses
+      let b = &self.b;
+      let a0 = self.a0.as_slice();
+      let (x0, v0) = (self.x0.as_mut_slice(), self.v0.as_mut_slice());
+      for k in 0..n3 {
+        let dx = dt_done * v0[k]
+          + dt_done * dt_done
+            * (a0[k] / 2.0 + b[0][k] / 6.0 + b[1][k] / 12.0 + b[2][k] / 20.0 + b[3][k] / 30.0 + b[4][k] / 42.0
+              + b[5][k] / 56.0 + b[6][k] / 72.0);
+        let dv = dt_done
+          * (a0[k] + b[0][k] / 2.0 + b[1][k] / 3.0 + b[2][k] / 4.0 + b[3][k] / 5.0 + b[4][k] / 6.0 + b[5][k] / 7.0
+            + b[6][k] / 8.0);
+        add_compensated(&mut x0[k], &mut self.csx[k], dx);
+        add_compensated(&mut v0[k], &mut self.csv[k], dv);
+      }
+
+      self.dt_last_success = dt_done.abs();
+      for (saved, current) in self.er.iter_mut().zip(&self.e).chain(self.br.iter_mut().zip(&self.b)) {
+        saved.copy_from_slice(current);
+      }
+      self.predict_next_step(dt_new / dt_done.abs());
+      self.dt = Some(dt_new);
+      state.positions.copy_from(&self.x0);
+      state.velocities.copy_from(&self.v0);
+      return Ok(dt_done);
+    }
+  }
+
+  fn reset(&mut self) {
+    // Force la resynchronisation au prochain pas
+    self.masses.clear();
+  }
+
+  fn rejected_steps(&self) -> u64 {
+    self.rejected
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::bll::gravity::G;
+  use crate::bll::integrator::fixtures::*;
+  use crate::bll::kepler;
+  use crate::bo::celest_item::CelestItem;
+  use crate::bo::simulation_options::{IntegratorKind, SimulationOptions};
+
+  #[test]
+  fn close_encounter_follows_the_exact_hyperbola() {
+    // Particule test frôlant un Soleil ponctuel à 10 000 km de son centre, à plus de 5 000 km/s
+    let gm = G * SUN_MASS;
+    let (periapsis, eccentricity, duration) = (1e7, 1.5, 6.0 * 3600.0);
+    let mut position = [periapsis, 0.0, 0.0];
+    let mut velocity = [0.0, (gm * (1.0 + eccentricity) / periapsis).sqrt(), 0.0];
+    kepler::propagate(&mut position, &mut velocity, gm, -0.5 * duration).unwrap();
+    let items = [
+      CelestItem { name: "Soleil".into(), mass: SUN_MASS, fixed: true, ..Default::default() },
+      CelestItem { name: "Comète".into(), position, velocity, ..Default::default() },
+    ];
+
+    let options = SimulationOptions { integrator: IntegratorKind::Ias15, ..Default::default() };
+    let (end, steps, _) = integrate(&options, &items, duration, duration);
+    kepler::propagate(&mut position, &mut velocity, gm, duration).unwrap();
+    let expected = CelestItem { position, ..Default::default() };
+    let scale = position[0].hypot(position[1]);
+    assert!(distance(&end[1], &expected) < 1e-8 * scale, "{}", distance(&end[1], &expected));
+
+    // Le même nombre de pas, répartis uniformément, ne résout pas le passage au périhélie
+    let verlet = SimulationOptions { integrator: IntegratorKind::VelocityVerlet, ..Default::default() };
+    let (uniform, _, _) = integrate(&verlet, &items, duration, duration / steps as f64);
+    assert!(distance(&uniform[1], &expected) > 0.1 * scale, "{}", distance(&uniform[1], &expected));
+  }
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+
+use crate::bll::dynamics::Dynamics;
+use crate::bll::integrator::Integrator;
+use crate::bll::state::{SystemState, Vectors};
+
+/// Leapfrog kick-drift-kick : demi-kick, drift complet, demi-kick
+///
+/// Le second demi-kick d'un pas et le premier du pas suivant utilisent les mêmes
+/// accélérations, conservées entre deux appels.
+pub struct Leapfrog {
+  dynamics: Dynamics,
+  accelerations: Vectors,
+  ready: bool,
+}
+
+impl Leapfrog {
+  pub fn new(dynamics: Dynamics) -> Self {
+    Leapfrog { dynamics, accelerations: Vectors::default(), ready: false }
+  }
+
+  fn update_accelerations(&mut self, state: &SystemState) {
+    self.dynamics.accelerations(&state.positions, &state.velocities, &state.masses, &state.force_parameters, &mut self.accelerations);
+    state.pin(&mut self.accelerations);
+  }
+}
+
+impl Integrator for Leapfrog {
+  fn step(&mut self, state: &mut SystemState, dt: f64) -> Result<f64> {
+    if !self.ready || self.accelerations.len() != state.len() {
+      self.update_accelerations(state);
+      self.ready = true;
+    }
+
+    state.velocities.add_scaled(&self.accelerations, 0.5 * dt);
+    state.positions.add_scaled(&state.velocities, dt);
+    self.update_accelerations(state);
+    state.velocities.add_scaled(&self.accelerations, 0.5 * dt);
+    Ok(dt)
+  }
+
+  fn reset(&mut self) {
+    self.ready = false;
+  }
+}
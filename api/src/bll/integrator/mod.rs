@@ -0,0 +1,153 @@
+pub mod dormand_prince;
+pub mod euler;
+pub mod ias15;
+pub mod leapfrog;
+pub mod two_body;
+pub mod velocity_verlet;
+pub mod wisdom_holman;
+
+use anyhow::Result;
+
+use crate::bll::dynamics::Dynamics;
+use crate::bll::state::SystemState;
+use crate::bo::simulation_options::{IntegratorKind, SimulationOptions};
+
+/// Interface commune des schémas d'intégration
+pub trait Integrator: Send {
+  /// Avance `state` d'au plus `dt` secondes (négatif pour remonter le temps)
+  /// et retourne la durée effectivement intégrée.
+  ///
+  /// Les schémas à pas fixe intègrent exactement `dt`, les schémas adaptatifs
+  /// peuvent s'arrêter avant. Une propagation képlérienne qui ne converge pas
+  /// interrompt l'intégration en erreur.
+  fn step(&mut self, state: &mut SystemState, dt: f64) -> Result<f64>;
+
+  /// Invalide les données conservées d'un pas à l'autre après une modification de l'état
+  /// hors de l'intégrateur (fusion, rebond)
+  fn reset(&mut self) {}
+
+  /// Nombre de pas rejetés par le contrôle d'erreur
+  fn rejected_steps(&self) -> u64 {
+    0
+  }
+}
+
+/// Instancie l'intégrateur correspondant aux options de la requête, pour les corps de `state`
+pub fn build(options: &SimulationOptions, state: &SystemState) -> Box<dyn Integrator> {
+  let dynamics = Dynamics::build(options, &state.force_parameters);
+  match options.integrator {
+    IntegratorKind::Euler => Box::new(euler::Euler::new(dynamics)),
+    IntegratorKind::VelocityVerlet => Box::new(velocity_verlet::VelocityVerlet::new(dynamics)),
+    IntegratorKind::Leapfrog => Box::new(leapfrog::Leapfrog::new(dynamics)),
+    IntegratorKind::DormandPrince => Box::new(dormand_prince::DormandPrince::new(options.tolerance, dynamics)),
+    IntegratorKind::Ias15 => Box::new(ias15::Ias15::new(dynamics)),
+    IntegratorKind::WisdomHolman => Box::new(wisdom_holman::WisdomHolman::new(dynamics)),
+    IntegratorKind::Kepler => Box::new(two_body::TwoBody),
+  }
+}
+
+
+/// Orbites de référence partagées par les tests des intégrateurs
+#[cfg(test)]
+pub mod fixtures {
+  use super::*;
+  use crate::bll::gravity::G;
+  use crate::bo::celest_item::CelestItem;
+
+  pub const SUN_MASS: f64 = 1.9885e30;
+  pub const PLANET_MASS: f64 = 5.97e24;
+  pub const SEMI_MAJOR_AXIS: f64 = 1.495_978_707e11;
+
+  /// Période de l'orbite relative de la planète autour du Soleil
+  pub fn period() -> f64 {
+    std::f64::consts::TAU * (SEMI_MAJOR_AXIS.powi(3) / (G * (SUN_MASS + PLANET_MASS))).sqrt()
+  }
+
+  /// Soleil et planète d'excentricité `eccentricity`, au périhélie, centre de masse immobile à l'origine
+  pub fn orbit(eccentricity: f64) -> Vec<CelestItem> {
+    let distance = SEMI_MAJOR_AXIS * (1.0 - eccentricity);
+    let speed = (G * (SUN_MASS + PLANET_MASS) * (1.0 + eccentricity) / distance).sqrt();
+    let share = PLANET_MASS / (SUN_MASS + PLANET_MASS);
+    let body = |name: &str, mass: f64, sign: f64, weight: f64| CelestItem {
+      name: name.into(),
+      mass,
+      radius: 1.0,
+      position: [sign * weight * distance, 0.0, 0.0],
+      velocity: [0.0, sign * weight * speed, 0.0],
+      ..Default::default()
+    };
+    vec![body("Soleil", SUN_MASS, -1.0, share), body("Planète", PLANET_MASS, 1.0, 1.0 - share)]
+  }
+
+  /// Intègre `items` pendant `duration` secondes par pas d'au plus `dt`, et retourne l'état
+  /// final avec le nombre de pas acceptés et rejetés
+  pub fn integrate(options: &SimulationOptions, items: &[CelestItem], duration: f64, dt: f64) -> (Vec<CelestItem>, u64, u64) {
+    let mut state = SystemState::from_items(items);
+    let mut integrator = build(options, &state);
+    let (mut elapsed, mut steps) = (0.0_f64, 0);
+    while elapsed.abs() < duration.abs() {
+      let remaining = duration - elapsed;
+      elapsed += integrator.step(&mut state, remaining.signum() * remaining.abs().min(dt)).unwrap();
+      steps += 1;
+    }
+    let mut end = items.to_vec();
+    state.write_to(&mut end);
+    (end, steps, integrator.rejected_steps())
+  }
+
+  /// Écart relatif maximal de l'énergie à sa valeur initiale au cours de chacune des
+  /// `periods` périodes, intégrées par `steps` pas
+  pub fn energy_errors(options: &SimulationOptions, items: &[CelestItem], periods: usize, steps: usize) -> Vec<f64> {
+    let energy = |state: &SystemState| {
+      let mut current = items.to_vec();
+      state.write_to(&mut current);
+      crate::bll::diagnostics::conserved_quantities(&current).energy
+    };
+    let mut state = SystemState::from_items(items);
+    let mut integrator = build(options, &state);
+    let initial = energy(&state);
+    let dt = period() / steps as f64;
+    (0..periods)
+      .map(|_| {
+        (0..steps)
+          .map(|_| {
+            integrator.step(&mut state, dt).unwrap();
+            ((energy(&state) - initial) / initial).abs()
+          })
+          .fold(0.0, f64::max)
+      })
+      .collect()
+  }
+
+  pub fn distance(a: &CelestItem, b: &CelestItem) -> f64 {
+    (0..3).map(|k| (a.position[k] - b.position[k]).powi(2)).sum::<f64>().sqrt()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::fixtures::*;
+  use super::*;
+
+  #[test]
+  fn symplectic_schemes_bound_energy_error_and_reverse_exactly() {
+    for kind in [IntegratorKind::VelocityVerlet, IntegratorKind::Leapfrog] {
+      let options = SimulationOptions { integrator: kind, ..Default::default() };
+      let items = orbit(0.5);
+
+      // Schéma symplectique : l'erreur d'énergie oscille sans dériver au fil des révolutions
+      let errors = energy_errors(&options, &items, 200, 500);
+      let (early, late) = (errors[..20].iter().copied().fold(0.0, f64::max), errors[180..].iter().copied().fold(0.0, f64::max));
+      assert!(early < 1e-3, "{kind:?} : {early}");
+      assert!(late < 1.01 * early, "{kind:?} : {early} puis {late}");
+
+      // Schéma symétrique : des pas opposés ramènent à l'état initial, aux arrondis près
+      let dt = period() / 500.0;
+      let (forward, _, _) = integrate(&options, &items, 10.0 * period(), dt);
+      let (back, _, _) = integrate(&options, &forward, -10.0 * period(), dt);
+      let error = distance(&back[1], &items[1]);
+      assert!(error < 1e-10 * SEMI_MAJOR_AXIS, "{kind:?} : {error} m");
+    }
+  }
+}
@@ -0,0 +1,176 @@
+use anyhow::Result;
+
+use crate::bll::forces::dominant;
+use crate::bll::gravity::G;
+use crate::bll::integrator::Integrator;
+use crate::bll::kepler;
+use crate::bll::state::SystemState;
+
+/// Propagation analytique de chaque corps sur sa conique képlérienne autour du corps dominant
+///
+/// Les interactions entre corps secondaires et les forces non gravitationnelles sont
+/// ignorées : un seul pas couvre n'importe quel intervalle, en un temps indépendant de
+/// sa durée. Le corps dominant est placé de sorte que le centre de masse garde un
+/// mouvement uniforme ; s'il est fixe, il ne bouge pas.
+pub struct TwoBody;
+
+impl Integrator for TwoBody {
+  fn step(&mut self, state: &mut SystemState, dt: f64) -> Result<f64> {
+    let Some(primary) = dominant(&state.masses) else {
+      return Ok(dt);
+    };
+    let (origin, drift) = (state.positions.get(primary), state.velocities.get(primary));
+    let primary_mass = state.masses[primary];
+
+    // Centre de masse en mouvement uniforme, pondéré par les masses ; les corps fixes y restent à leur place
+    let mut barycenter = [0.0; 3];
+    let mut momentum = [0.0; 3];
+    let mut fixed = [0.0; 3];
+    let mut mobile_mass = primary_mass;
+    for i in 0..state.len() {
+      let (position, velocity, mass) = (state.positions.get(i), state.velocities.get(i), state.masses[i]);
+      for k in 0..3 {
+        barycenter[k] += mass * (position[k] + velocity[k] * dt);
+        momentum[k] += mass * velocity[k];
+      }
+      if state.is_fixed_index(i) {
+        for k in 0..3 {
+          fixed[k] += mass * position[k];
+        }
+      } else if i != primary {
+        mobile_mass += mass;
+      }
+    }
+
+    // Positions et vitesses relatives au corps dominant, propagées sur leur conique
+    let mut weighted = ([0.0; 3], [0.0; 3]);
+    let mut relatives = Vec::with_capacity(state.len());
+    for i in 0..state.len() {
+      if i == primary || state.is_fixed_index(i) {
+        continue;
+      }
+      let (position, velocity) = (state.positions.get(i), state.velocities.get(i));
+      let mut r: [f64; 3] = std::array::from_fn(|k| position[k] - origin[k]);
+      let mut v: [f64; 3] = std::array::from_fn(|k| velocity[k] - drift[k]);
+      kepler::propagate(&mut r, &mut v, G * (primary_mass + state.masses[i]), dt)?;
+      for k in 0..3 {
+        weighted.0[k] += state.masses[i] * r[k];
+        weighted.1[k] += state.masses[i] * v[k];
+      }
+      relatives.push((i, r, v));
+    }
+
+    let (origin, drift) = if state.is_fixed_index(primary) || mobile_mass <= 0.0 {
+      (origin, drift)
+    } else {
+      (
+        std::array::from_fn(|k| (barycenter[k] - fixed[k] - weighted.0[k]) / mobile_mass),
+        std::array::from_fn(|k| (momentum[k] - weighted.1[k]) / mobile_mass),
+      )
+    };
+    state.positions.set(primary, origin);
+    state.velocities.set(primary, drift);
+    for (i, r, v) in relatives {
+      state.positions.set(i, std::array::from_fn(|k| origin[k] + r[k]));
+      state.velocities.set(i, std::array::from_fn(|k| drift[k] + v[k]));
+    }
+    Ok(dt)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::f64::consts::PI;
+
+  use crate::bo::celest_item::CelestItem;
+
+  #[test]
+  fn circular_orbit_after_a_thousand_periods() {
+    let (sun, earth, a) = (1.9885e30, 5.97e24, 1.495_978_707e11);
+    let gm = G * (sun + earth);
+    let speed = (gm / a).sqrt();
+    // Conditions barycentriques : le centre de masse reste à l'origine
+    let item = |name: &str, mass: f64, x: f64, vy: f64| CelestItem {
+      name: name.into(),
+      mass,
+      radius: 1.0,
+      position: [x, 0.0, 0.0],
+      velocity: [0.0, vy, 0.0],
+      ..Default::default()
+    };
+    let share = earth / (sun + earth);
+    let mut state = SystemState::from_items(&[
+      item("Soleil", sun, -share * a, -share * speed),
+      item("Terre", earth, (1.0 - share) * a, (1.0 - share) * speed),
+    ]);
+
+    let period = 2.0 * PI * (a.powi(3) / gm).sqrt();
+    assert_eq!(TwoBody.step(&mut state, 1000.25 * period).unwrap(), 1000.25 * period);
+
+    let (s, e) = (state.positions.get(0), state.positions.get(1));
+    let relative: [f64; 3] = std::array::from_fn(|k| e[k] - s[k]);
+    assert!(relative[0].abs() < 1e-6 * a && (relative[1] - a).abs() < 1e-6 * a, "{relative:?}");
+    let barycenter: [f64; 3] = std::array::from_fn(|k| (sun * s[k] + earth * e[k]) / (sun + earth));
+    assert!(barycenter.iter().all(|x| x.abs() < 1.0), "{barycenter:?}");
+    assert!((state.velocities.get(1)[0] + (1.0 - share) * speed).abs() < 1e-3);
+  }
+
+  /// Soleil fixe à l'origine et particule test au périapside, sur l'axe x
+  fn flyby(periapsis: f64, eccentricity: f64) -> SystemState {
+    let gm = G * 1.9885e30;
+    let speed = (gm * (1.0 + eccentricity) / periapsis).sqrt();
+    SystemState::from_items(&[
+      CelestItem { name: "Soleil".into(), mass: 1.9885e30, fixed: true, ..Default::default() },
+      CelestItem { name: "Comète".into(), position: [periapsis, 0.0, 0.0], velocity: [0.0, speed, 0.0], ..Default::default() },
+    ])
+  }
+
+  #[test]
+  fn hyperbolic_flyby_over_short_and_very_long_steps() {
+    let (q, e) = (1.495_978_707e11_f64, 2.0);
+    let gm = G * 1.9885e30;
+    let a = q / (e - 1.0); // demi-axe de l'hyperbole, en valeur absolue
+    let n = (gm / a.powi(3)).sqrt();
+
+    // Jusqu'à trente millions d'années en un seul pas, dans les deux sens
+    for dt in [86_400.0, 3.15e7, 1e12, 1e15, -3.15e7, -1e15] {
+      let mut state = flyby(q, e);
+      assert_eq!(TwoBody.step(&mut state, dt).unwrap(), dt);
+
+      // Équation de Kepler hyperbolique e sinh H − H = n t, résolue par Newton
+      let mean = n * dt.abs();
+      let mut h = (2.0 * mean / e + 1.8).ln();
+      for _ in 0..100 {
+        h -= (e * h.sinh() - h - mean) / (e * h.cosh() - 1.0);
+      }
+      let expected = [a * (e - h.cosh()), dt.signum() * a * (e * e - 1.0).sqrt() * h.sinh()];
+      let position = state.positions.get(1);
+      let scale = a * (e * h.cosh() - 1.0);
+      assert!((0..2).all(|k| (position[k] - expected[k]).abs() < 1e-9 * scale), "{dt} {position:?} {expected:?}");
+      assert_eq!(state.positions.get(0), [0.0; 3]);
+    }
+  }
+
+  #[test]
+  fn elliptic_step_of_a_hundred_million_periods() {
+    let (q, e) = (1.495_978_707e11_f64, 0.5);
+    let a = q / (1.0 - e);
+    let period = 2.0 * PI * (a.powi(3) / (G * 1.9885e30)).sqrt();
+    let mut state = flyby(q, e);
+    TwoBody.step(&mut state, 1e8 * period + 0.5 * period).unwrap();
+    // Demi-période plus tard : à l'aphélie, de l'autre côté du Soleil
+    let position = state.positions.get(1);
+    assert!((position[0] + a * (1.0 + e)).abs() < 1e-6 * a && position[1].abs() < 1e-6 * a, "{position:?}");
+  }
+
+  #[test]
+  fn non_finite_steps_are_errors() {
+    let mut state = flyby(1.495_978_707e11, 0.5);
+    for dt in [f64::NAN, f64::INFINITY] {
+      assert!(TwoBody.step(&mut state, dt).is_err());
+    }
+    assert_eq!(state.positions.get(1), [1.495_978_707e11, 0.0, 0.0]);
+  }
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+
+use crate::bll::dynamics::Dynamics;
+use crate::bll::integrator::Integrator;
+use crate::bll::state::{SystemState, Vectors};
+
+/// Velocity Verlet : x(t+dt) = x + v·dt + a·dt²/2 puis v(t+dt) = v + (a + a')·dt/2
+///
+/// Les accélérations de fin de pas sont conservées pour le pas suivant,
+/// soit une seule évaluation des forces par pas. Les deux tampons
+/// d'accélérations sont échangés à chaque pas.
+pub struct VelocityVerlet {
+  dynamics: Dynamics,
+  accelerations: Vectors,
+  next: Vectors,
+  ready: bool,
+}
+
+impl VelocityVerlet {
+  pub fn new(dynamics: Dynamics) -> Self {
+    VelocityVerlet { dynamics, accelerations: Vectors::default(), next: Vectors::default(), ready: false }
+  }
+}
+
+impl Integrator for VelocityVerlet {
+  fn step(&mut self, state: &mut SystemState, dt: f64) -> Result<f64> {
+    if !self.ready || self.accelerations.len() != state.len() {
+      self.dynamics.accelerations(&state.positions, &state.velocities, &state.masses, &state.force_parameters, &mut self.accelerations);
+      state.pin(&mut self.accelerations);
+      self.ready = true;
+    }
+
+    // 1. Mise à jour des positions
+    state.positions.add_scaled(&state.velocities, dt);
+    state.positions.add_scaled(&self.accelerations, 0.5 * dt * dt);
+
+    // 2. Mise à jour des vitesses avec la moyenne des accélérations, la nouvelle
+    //    étant évaluée avec la vitesse prédite v + a·dt
+    state.velocities.add_scaled(&self.accelerations, dt);
+    self.dynamics.accelerations(&state.positions, &state.velocities, &state.masses, &state.force_parameters, &mut self.next);
+    state.pin(&mut self.next);
+    state.velocities.add_scaled(&self.accelerations, -0.5 * dt);
+    state.velocities.add_scaled(&self.next, 0.5 * dt);
+
+    std::mem::swap(&mut self.accelerations, &mut self.next);
+    Ok(dt)
+  }
+
+  fn reset(&mut self) {
+    self.ready = false;
+  }
+}
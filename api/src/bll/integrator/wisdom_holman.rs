@@ -0,0 +1,295 @@
+// Noyau numérique indexé composante par composante, plus lisible avec des boucles explicites
+#![allow(clippy::needless_range_loop)]
+
+use anyhow::Result;
+
+use crate::bll::dynamics::Dynamics;
+use crate::bll::gravity::{G, MIN_DISTANCE};
+use crate::bll::integrator::Integrator;
+use crate::bll::kepler;
+use crate::bll::state::{SystemState, Vectors};
+use crate::bo::force_parameters::ForceParameters;
+
+/// Application de Wisdom–Holman en coordonnées de Jacobi (à la WHFast)
+///
+/// Le mouvement est découpé en dérives képlériennes exactes de chaque corps autour
+/// des masses intérieures et en impulsions dues aux seules perturbations mutuelles,
+/// selon le schéma drift-kick-drift. L'erreur est proportionnelle au rapport des
+/// masses perturbatrices sur la masse dominante, ce qui autorise des pas de l'ordre
+/// du vingtième de la plus courte période orbitale sur des millions d'années.
+///
+/// Les corps sont ordonnés à partir du plus massif par distance croissante à
+/// celui-ci, les particules test en dernier. L'intégration se fait dans un repère inertiel ; si le corps dominant
+/// est fixe, les états renvoyés sont exprimés relativement à lui.
+pub struct WisdomHolman {
+  dynamics: Dynamics,
+  // Indices dans l'état : corps dominant puis corps par distance croissante
+  order: Vec<usize>,
+  masses: Vec<f64>,
+  parameters: Vec<ForceParameters>,
+  // Masses intérieures cumulées η_i = m_0 + … + m_i
+  eta: Vec<f64>,
+  jacobi_positions: Vec<[f64; 3]>,
+  jacobi_velocities: Vec<[f64; 3]>,
+  // Tampons réutilisés pour le retour en inertiel et l'appel au solveur
+  inertial: Vec<[f64; 3]>,
+  positions: Vectors,
+  velocities: Vectors,
+  accelerations: Vectors,
+  // Dernier état renvoyé, pour détecter une modification extérieure
+  synced: SystemState,
+}
+
+/// Inertiel → Jacobi : position relative au centre de masse des corps intérieurs
+fn inertial_to_jacobi(masses: &[f64], eta: &[f64], vectors: &[[f64; 3]], jacobi: &mut [[f64; 3]]) {
+  let n = vectors.len();
+  if n == 0 {
+    return;
+  }
+  let mut weighted = vectors[0].map(|x| x * masses[0]);
+  for i in 1..n {
+    for k in 0..3 {
+      jacobi[i][k] = vectors[i][k] - weighted[k] / eta[i - 1];
+      weighted[k] += masses[i] * vectors[i][k];
+    }
+  }
+  // La coordonnée 0 porte le centre de masse global
+  jacobi[0] = weighted.map(|x| x / eta[n - 1]);
+}
+
+/// Jacobi → inertiel, en remontant depuis le centre de masse global
+fn jacobi_to_inertial(masses: &[f64], eta: &[f64], jacobi: &[[f64; 3]], vectors: &mut [[f64; 3]]) {
+  let n = jacobi.len();
+  if n == 0 {
+    return;
+  }
+  let mut center = jacobi[0];
+  for i in (1..n).rev() {
+    for k in 0..3 {
+      center[k] -= masses[i] / eta[i] * jacobi[i][k];
+      vectors[i][k] = jacobi[i][k] + center[k];
+    }
+  }
+  vectors[0] = center;
+}
+
+impl WisdomHolman {
+  pub fn new(dynamics: Dynamics) -> Self {
+    WisdomHolman {
+      dynamics,
+      order: Vec::new(),
+      masses: Vec::new(),
+      parameters: Vec::new(),
+      eta: Vec::new(),
+      jacobi_positions: Vec::new(),
+      jacobi_velocities: Vec::new(),
+      inertial: Vec::new(),
+      positions: Vectors::default(),
+      velocities: Vectors::default(),
+      accelerations: Vectors::default(),
+      synced: SystemState::default(),
+    }
+  }
+
+  /// Recalcule les coordonnées de Jacobi si l'état a été modifié hors de l'intégrateur
+  fn sync(&mut self, state: &SystemState) {
+    let unchanged = self.synced.positions == state.positions
+      && self.synced.velocities == state.velocities
+      && self.synced.masses == state.masses;
+    if unchanged {
+      return;
+    }
+
+    let n = state.len();
+    let central = (0..n)
+      .max_by(|&a, &b| state.masses[a].total_cmp(&state.masses[b]))
+      .unwrap_or(0);
+    let origin = state.positions.get(central);
+    let distance = |i: usize| -> f64 {
+      let position = state.positions.get(i);
+      (0..3).map(|k| (position[k] - origin[k]).powi(2)).sum()
+    };
+    let mut others: Vec<usize> = (0..n).filter(|&i| i != central).collect();
+    // Particules test après les corps massifs, qui restent seuls sources de gravitation
+    let particle = |i: usize| state.masses[i] == 0.0;
+    others.sort_by(|&a, &b| particle(a).cmp(&particle(b)).then(distance(a).total_cmp(&distance(b))));
+
+    self.order = std::iter::once(central).chain(others).collect();
+    self.masses = self.order.iter().map(|&i| state.masses[i]).collect();
+    self.parameters = self.order.iter().map(|&i| state.force_parameters[i]).collect();
+    self.eta = self
+      .masses
+      .iter()
+      .scan(0.0, |sum, m| {
+        *sum += m;
+        Some(*sum)
+      })
+      .collect();
+
+    self.jacobi_positions = vec![[0.0; 3]; n];
+    self.jacobi_velocities = vec![[0.0; 3]; n];
+    self.inertial = self.order.iter().map(|&i| state.positions.get(i)).collect();
+    inertial_to_jacobi(&self.masses, &self.eta, &self.inertial, &mut self.jacobi_positions);
+    self.inertial = self.order.iter().map(|&i| state.velocities.get(i)).collect();
+    inertial_to_jacobi(&self.masses, &self.eta, &self.inertial, &mut self.jacobi_velocities);
+    self.positions.resize(n);
+    self.velocities.resize(n);
+    self.accelerations.resize(n);
+    self.synced = state.clone();
+  }
+
+  /// Dérive képlérienne de chaque coordonnée de Jacobi autour des masses intérieures
+  fn drift(&mut self, dt: f64) -> Result<()> {
+    for i in 1..self.order.len() {
+      kepler::propagate(&mut self.jacobi_positions[i], &mut self.jacobi_velocities[i], G * self.eta[i], dt)?;
+    }
+    let center_velocity = self.jacobi_velocities[0];
+    for k in 0..3 {
+      self.jacobi_positions[0][k] += center_velocity[k] * dt;
+    }
+    Ok(())
+  }
+
+  /// Impulsion due aux interactions non prises en compte par les dérives
+  fn kick(&mut self, dt: f64) {
+    let n = self.order.len();
+    if self.dynamics.depends_on_velocity() {
+      jacobi_to_inertial(&self.masses, &self.eta, &self.jacobi_velocities, &mut self.inertial);
+      for (slot, velocity) in self.inertial.iter().enumerate() {
+        self.velocities.set(slot, *velocity);
+      }
+    }
+    jacobi_to_inertial(&self.masses, &self.eta, &self.jacobi_positions, &mut self.inertial);
+    for (slot, position) in self.inertial.iter().enumerate() {
+      self.positions.set(slot, *position);
+    }
+
+    // Accélérations inertielles privées de l'interaction corps dominant / premier corps,
+    // déjà intégrée exactement par la première dérive képlérienne
+    self.dynamics.accelerations(&self.positions, &self.velocities, &self.masses, &self.parameters, &mut self.accelerations);
+    if n > 1 {
+      let positions = &self.inertial;
+      let d: [f64; 3] = std::array::from_fn(|k| positions[1][k] - positions[0][k]);
+      let r_squared = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+      let r = r_squared.sqrt();
+      if r >= MIN_DISTANCE {
+        let factor = G / (r_squared * r);
+        let (mut a0, mut a1) = (self.accelerations.get(0), self.accelerations.get(1));
+        for k in 0..3 {
+          a0[k] -= factor * self.masses[1] * d[k];
+          a1[k] += factor * self.masses[0] * d[k];
+        }
+        self.accelerations.set(0, a0);
+        self.accelerations.set(1, a1);
+      }
+    }
+
+    // Passage en Jacobi et retrait du terme képlérien des corps extérieurs
+    let mut weighted = self.accelerations.get(0).map(|a| a * self.masses[0]);
+    for i in 1..n {
+      let acceleration = self.accelerations.get(i);
+      let rj = self.jacobi_positions[i];
+      let rj_squared = rj[0] * rj[0] + rj[1] * rj[1] + rj[2] * rj[2];
+      let kepler_term = if i > 1 && rj_squared > 0.0 {
+        G * self.eta[i] / (rj_squared * rj_squared.sqrt())
+      } else {
+        0.0
+      };
+      for k in 0..3 {
+        let jacobi_acc = acceleration[k] - weighted[k] / self.eta[i - 1];
+        self.jacobi_velocities[i][k] += dt * (jacobi_acc + kepler_term * rj[k]);
+        weighted[k] += self.masses[i] * acceleration[k];
+      }
+    }
+
+    // La résultante, nulle pour les seules interactions mutuelles, porte les forces
+    // extérieures (poussée, pression de radiation) et déplace le centre de masse
+    for k in 0..3 {
+      self.jacobi_velocities[0][k] += dt * weighted[k] / self.eta[n - 1];
+    }
+  }
+
+  fn write_back(&mut self, state: &mut SystemState) {
+    // Corps dominant fixe : états exprimés relativement à sa position et sa vitesse d'origine
+    let central = self.order[0];
+    let fixed = state.is_fixed_index(central);
+
+    jacobi_to_inertial(&self.masses, &self.eta, &self.jacobi_positions, &mut self.inertial);
+    let shift = if fixed {
+      let origin = state.positions.get(central);
+      std::array::from_fn(|k| origin[k] - self.inertial[0][k])
+    } else {
+      [0.0; 3]
+    };
+    for (slot, &i) in self.order.iter().enumerate() {
+      state.positions.set(i, std::array::from_fn(|k| self.inertial[slot][k] + shift[k]));
+    }
+
+    jacobi_to_inertial(&self.masses, &self.eta, &self.jacobi_velocities, &mut self.inertial);
+    let shift = if fixed {
+      let origin = state.velocities.get(central);
+      std::array::from_fn(|k| origin[k] - self.inertial[0][k])
+    } else {
+      [0.0; 3]
+    };
+    for (slot, &i) in self.order.iter().enumerate() {
+      state.velocities.set(i, std::array::from_fn(|k| self.inertial[slot][k] + shift[k]));
+    }
+
+    self.synced.positions.copy_from(&state.positions);
+    self.synced.velocities.copy_from(&state.velocities);
+  }
+}
+
+impl Integrator for WisdomHolman {
+  fn step(&mut self, state: &mut SystemState, dt: f64) -> Result<f64> {
+    if state.is_empty() {
+      return Ok(dt);
+    }
+    self.sync(state);
+
+    self.drift(0.5 * dt)?;
+    self.kick(dt);
+    self.drift(0.5 * dt)?;
+
+    self.write_back(state);
+    Ok(dt)
+  }
+
+  fn reset(&mut self) {
+    // Force le recalcul des coordonnées de Jacobi au prochain pas
+    self.synced = SystemState::default();
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::bll::gravity::G;
+  use crate::bll::integrator::fixtures::*;
+  use crate::bo::celest_item::CelestItem;
+  use crate::bo::simulation_options::{IntegratorKind, SimulationOptions};
+
+  #[test]
+  fn energy_error_far_below_verlet_over_long_runs() {
+    // Planète légèrement excentrique et géante extérieure sur orbite circulaire à 5,2 ua
+    let mut items = orbit(0.05);
+    let (mass, distance) = (1.898e27, 5.2 * SEMI_MAJOR_AXIS);
+    let speed = (G * SUN_MASS / distance).sqrt();
+    items.push(CelestItem {
+      name: "Géante".into(),
+      mass,
+      radius: 1.0,
+      position: [0.0, distance, 0.0],
+      velocity: [-speed, 0.0, 0.0],
+      ..Default::default()
+    });
+
+    // Cinquante pas par révolution intérieure, sur deux cents révolutions
+    let max = |errors: &[f64]| errors.iter().copied().fold(0.0, f64::max);
+    let run = |integrator| energy_errors(&SimulationOptions { integrator, ..Default::default() }, &items, 200, 50);
+    let (wisdom_holman, verlet) = (run(IntegratorKind::WisdomHolman), run(IntegratorKind::VelocityVerlet));
+    assert!(max(&wisdom_holman) < max(&verlet) / 1000.0, "{} {}", max(&wisdom_holman), max(&verlet));
+    assert!(max(&wisdom_holman[180..]) < 1.1 * max(&wisdom_holman[..20]), "{wisdom_holman:?}");
+  }
+}
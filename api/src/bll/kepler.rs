@@ -0,0 +1,125 @@
+use std::f64::consts::PI;
+
+use anyhow::{bail, Result};
+
+const MAX_ITERATIONS: usize = 50;
+const LAGUERRE_ORDER: f64 = 5.0;
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Fonctions de Stumpff c0..c3
+fn stumpff(z: f64) -> (f64, f64, f64, f64) {
+  if z > 0.1 {
+    let s = z.sqrt();
+    let c0 = s.cos();
+    let c1 = s.sin() / s;
+    (c0, c1, (1.0 - c0) / z, (1.0 - c1) / z)
+  } else if z < -0.1 {
+    let s = (-z).sqrt();
+    let c0 = s.cosh();
+    let c1 = s.sinh() / s;
+    (c0, c1, (1.0 - c0) / z, (1.0 - c1) / z)
+  } else {
+    // Développement en série : c_k(z) = Σ (-z)^n / (2n + k)!
+    let mut c2 = 0.0;
+    let mut c3 = 0.0;
+    let mut term2 = 0.5;
+    let mut term3 = 1.0 / 6.0;
+    for n in 1..12 {
+      c2 += term2;
+      c3 += term3;
+      let n = n as f64;
+      term2 *= -z / ((2.0 * n + 1.0) * (2.0 * n + 2.0));
+      term3 *= -z / ((2.0 * n + 2.0) * (2.0 * n + 3.0));
+    }
+    (1.0 - z * c2, 1.0 - z * c3, c2, c3)
+  }
+}
+
+/// Propage un état relatif sur sa conique képlérienne pendant `dt` secondes
+///
+/// Formulation en variable universelle (Danby), valable pour les orbites
+/// elliptiques, paraboliques et hyperboliques. `gm` est le paramètre
+/// gravitationnel du problème à deux corps (m³/s²). L'état n'est pas modifié si
+/// l'équation de Kepler ne converge pas.
+pub fn propagate(position: &mut [f64; 3], velocity: &mut [f64; 3], gm: f64, dt: f64) -> Result<()> {
+  if !dt.is_finite() {
+    bail!("durée de propagation képlérienne non finie");
+  }
+  let r0 = dot(position, position).sqrt();
+  if r0 == 0.0 || gm <= 0.0 || dt == 0.0 {
+    for k in 0..3 {
+      position[k] += velocity[k] * dt;
+    }
+    return Ok(());
+  }
+  let eta0 = dot(position, velocity);
+  let beta = 2.0 * gm / r0 - dot(velocity, velocity);
+  let zeta0 = gm - beta * r0;
+
+  // Sur une ellipse, on retire les révolutions complètes (les fonctions f et g sont périodiques)
+  let mut dt_reduced = dt;
+  let mut x = if beta > 0.0 {
+    let period = 2.0 * PI * gm / beta.powf(1.5);
+    dt_reduced = dt % period;
+    beta * dt_reduced / gm
+  } else {
+    // Sur une hyperbole, estimation logarithmique (Vallado) : dt / r0 ferait déborder les
+    // fonctions de Stumpff pour les longues durées
+    let a = gm / beta;
+    let denominator = eta0 + dt.signum() * (-gm * a).sqrt() * (1.0 - r0 / a);
+    let chi = dt.signum() * (-a).sqrt() * (-2.0 * gm * dt / (a * denominator)).ln();
+    let guess = chi / gm.sqrt();
+    if guess.is_finite() && beta < 0.0 { guess } else { dt / r0 }
+  };
+
+  // Itérations de Laguerre–Conway sur l'équation de Kepler universelle
+  let mut g = (0.0, 0.0, 0.0, 0.0);
+  let mut r = r0;
+  let mut converged = false;
+  for _ in 0..MAX_ITERATIONS {
+    let (c0, c1, c2, c3) = stumpff(beta * x * x);
+    g = (c0, x * c1, x * x * c2, x * x * x * c3);
+    let f = r0 * g.1 + eta0 * g.2 + gm * g.3 - dt_reduced;
+    r = r0 * g.0 + eta0 * g.1 + gm * g.2;
+    let r_prime = eta0 * g.0 + zeta0 * g.1;
+    // Résidu au niveau de l'arrondi des termes de l'équation : x ne peut plus s'améliorer
+    let scale = (r0 * g.1).abs() + (eta0 * g.2).abs() + (gm * g.3).abs();
+    if f.abs() <= 16.0 * f64::EPSILON * scale {
+      converged = r.is_finite();
+      break;
+    }
+
+    let n = LAGUERRE_ORDER;
+    let discriminant = ((n - 1.0).powi(2) * r * r - n * (n - 1.0) * f * r_prime).abs().sqrt();
+    let dx = -n * f / (r + r.signum() * discriminant);
+    x += dx;
+    if dx.abs() <= 1e-15 * x.abs() {
+      let (c0, c1, c2, c3) = stumpff(beta * x * x);
+      g = (c0, x * c1, x * x * c2, x * x * x * c3);
+      r = r0 * g.0 + eta0 * g.1 + gm * g.2;
+      converged = r.is_finite();
+      break;
+    }
+  }
+  if !converged {
+    bail!("équation de Kepler non résolue en {MAX_ITERATIONS} itérations (dt = {dt} s)");
+  }
+
+  // Fonctions de Lagrange f, g et leurs dérivées
+  let (_, g1, g2, _) = g;
+  let f = 1.0 - gm * g2 / r0;
+  let g_lagrange = r0 * g1 + eta0 * g2;
+  let f_dot = -gm * g1 / (r * r0);
+  let g_dot = 1.0 - gm * g2 / r;
+
+  let r_vec = *position;
+  let v_vec = *velocity;
+  for k in 0..3 {
+    position[k] = f * r_vec[k] + g_lagrange * v_vec[k];
+    velocity[k] = f_dot * r_vec[k] + g_dot * v_vec[k];
+  }
+  Ok(())
+}
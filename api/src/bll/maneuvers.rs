@@ -0,0 +1,402 @@
+use anyhow::{Context, Result};
+
+use crate::bll::forces::{dominant, relative};
+use crate::bll::frames;
+use crate::bll::state::SystemState;
+use crate::bo::celest_item::CelestItem;
+use crate::bo::epoch::Epoch;
+use crate::bo::force_parameters::{Thrust, ThrustDirection};
+use crate::bo::spacecraft::{ExecutedImpulse, Maneuver, ManeuverFrame};
+
+/// Pesanteur normale g₀, qui relie impulsion spécifique et vitesse d'éjection (m/s²)
+pub const STANDARD_GRAVITY: f64 = 9.80665;
+
+// Part maximale de la masse d'un engin consommée pendant un pas de poussée continue
+const MAX_MASS_FRACTION: f64 = 1e-3;
+// En deçà de cet écart (s), une échéance est considérée comme atteinte
+const EPSILON: f64 = 1e-6;
+
+struct Impulse {
+  craft: String,
+  epoch: Epoch,
+  time: f64, // en secondes depuis le début de l'intégration
+  delta_v: [f64; 3],
+  frame: ManeuverFrame,
+  exhaust_speed: f64,
+}
+
+struct Burn {
+  craft: String,
+  start: f64, // en secondes depuis le début de l'intégration
+  end: f64,
+  thrust: f64,
+  flow: f64, // débit d'ergols, en kg/s
+  direction: ThrustDirection,
+  exhausted: bool,
+}
+
+impl Burn {
+  /// Indique si la poussée s'exerce sur le pas qui commence à `now` dans le sens `sign`
+  fn is_active(&self, sign: f64, now: f64) -> bool {
+    let (from, to) = if sign > 0.0 { (self.start, self.end) } else { (-self.end, -self.start) };
+    let at = sign * now;
+    at >= from - EPSILON && at < to - EPSILON
+  }
+}
+
+fn index(items: &[CelestItem], name: &str) -> Option<usize> {
+  items.iter().position(|item| item.name == name)
+}
+
+/// Retire `amount` kg d'ergols à l'engin `i` (négatif pour en restituer)
+fn consume(state: &mut SystemState, items: &mut [CelestItem], i: usize, amount: f64) {
+  let item = &mut items[i];
+  item.mass -= amount;
+  if let Some(craft) = item.spacecraft.as_mut() {
+    craft.propellant -= amount;
+  }
+  // La masse d'une particule test reste nulle dans l'intégration
+  if !item.test_particle {
+    state.masses[i] -= amount;
+  }
+}
+
+/// Exécution des manœuvres des engins spatiaux à leurs dates
+///
+/// Les pas d'intégration sont découpés aux dates des impulsions et aux bornes des
+/// poussées continues, si bien que chaque manœuvre s'applique à l'instant prévu quel
+/// que soit l'intégrateur. Une poussée continue est confiée à la force de poussée,
+/// avec l'accélération F/m de la masse à mi-pas ; le pas est alors limité pour que la
+/// masse varie peu. En remontant le temps, les impulsions sont annulées et les ergols restitués,
+/// à hauteur de ce que chaque impulsion a effectivement obtenu.
+pub struct ManeuverScheduler {
+  sign: f64,
+  // Impulsions de l'intervalle intégré, dans l'ordre où elles sont rencontrées
+  impulses: Vec<Impulse>,
+  next: usize,
+  burns: Vec<Burn>,
+}
+
+impl ManeuverScheduler {
+  /// Programme des engins de `items` pour une intégration de `delta_seconds` depuis `start_date`
+  ///
+  /// L'état d'un engin à une date inclut les impulsions prévues à cette date.
+  pub fn new(items: &[CelestItem], start_date: Epoch, delta_seconds: f64) -> Result<Self> {
+    let sign = if delta_seconds >= 0.0 { 1.0 } else { -1.0 };
+    let seconds = |date: Epoch| date.seconds_since(start_date);
+    let mut impulses = Vec::new();
+    let mut burns = Vec::new();
+
+    for item in items {
+      let Some(craft) = &item.spacecraft else {
+        continue;
+      };
+      craft.validate(item.mass, item.forces.as_ref()).with_context(|| format!("engin spatial {}", item.name))?;
+      for maneuver in &craft.maneuvers {
+        match *maneuver {
+          Maneuver::Impulsive { epoch, delta_v, frame, isp } => {
+            let time = seconds(epoch);
+            let within = if sign > 0.0 { time > 0.0 && time <= delta_seconds } else { time <= 0.0 && time > delta_seconds };
+            if within {
+              let exhaust_speed = isp * STANDARD_GRAVITY;
+              impulses.push(Impulse { craft: item.name.clone(), epoch, time, delta_v, frame, exhaust_speed });
+            }
+          }
+          Maneuver::Finite { start, duration, thrust, isp, direction } => {
+            let start = seconds(start);
+            burns.push(Burn {
+              craft: item.name.clone(),
+              start,
+              end: start + duration,
+              thrust,
+              flow: thrust / (isp * STANDARD_GRAVITY),
+              direction,
+              exhausted: false,
+            });
+          }
+        }
+      }
+    }
+    impulses.sort_by(|a, b| (sign * a.time).total_cmp(&(sign * b.time)));
+
+    Ok(ManeuverScheduler { sign, impulses, next: 0, burns })
+  }
+
+  /// Prépare le pas `dt` demandé à `now` : durée réduite pour s'arrêter à la prochaine
+  /// échéance et accélération de poussée évaluée avec la masse de l'engin à mi-pas
+  pub fn prepare(&self, now: f64, dt: f64, state: &mut SystemState, items: &[CelestItem]) -> f64 {
+    let mut horizon = dt.abs();
+    let mut bound = |time: f64| {
+      let ahead = self.sign * (time - now);
+      if ahead > EPSILON {
+        horizon = horizon.min(ahead);
+      }
+    };
+    if let Some(impulse) = self.impulses.get(self.next) {
+      bound(impulse.time);
+    }
+    for burn in &self.burns {
+      bound(burn.start);
+      bound(burn.end);
+    }
+
+    let mut active = Vec::new();
+    for burn in self.burns.iter().filter(|burn| burn.is_active(self.sign, now)) {
+      let Some(i) = index(items, &burn.craft) else {
+        continue;
+      };
+      let propellant = items[i].spacecraft.as_ref().map_or(0.0, |craft| craft.propellant);
+      if self.sign > 0.0 && propellant <= 0.0 {
+        continue;
+      }
+      if burn.flow > 0.0 {
+        horizon = horizon.min(MAX_MASS_FRACTION * items[i].mass / burn.flow);
+        if self.sign > 0.0 {
+          horizon = horizon.min(propellant / burn.flow);
+        }
+      }
+      active.push((i, burn));
+    }
+
+    // Poussée nulle hors des poussées actives
+    for burn in &self.burns {
+      if let Some(i) = index(items, &burn.craft)
+        && let Some(thrust) = state.force_parameters[i].thrust.as_mut()
+      {
+        thrust.acceleration = 0.0;
+      }
+    }
+    for (i, burn) in active {
+      let mass = items[i].mass - 0.5 * self.sign * burn.flow * horizon;
+      state.force_parameters[i].thrust = Some(Thrust { acceleration: burn.thrust / mass, direction: burn.direction });
+    }
+    dt.signum() * horizon
+  }
+
+  /// Applique les manœuvres du pas qui vient de mener de `previous` à `now` ;
+  /// indique si l'état ou les forces ont été modifiés
+  pub fn apply(&mut self, previous: f64, now: f64, state: &mut SystemState, items: &mut [CelestItem]) -> bool {
+    let mut changed = false;
+
+    // Ergols consommés par les poussées actives pendant le pas écoulé
+    let elapsed = now - previous;
+    for burn in self.burns.iter_mut() {
+      if elapsed == 0.0 || !burn.is_active(self.sign, previous) {
+        continue;
+      }
+      let Some(i) = index(items, &burn.craft) else {
+        continue;
+      };
+      let propellant = items[i].spacecraft.as_ref().map_or(0.0, |craft| craft.propellant);
+      let consumed = (burn.flow * elapsed).min(propellant);
+      consume(state, items, i, consumed);
+      changed |= consumed != 0.0;
+      if self.sign > 0.0 && propellant > 0.0 && propellant - consumed <= 0.0 && !burn.exhausted {
+        burn.exhausted = true;
+        tracing::warn!("⛽ {} : ergols épuisés pendant une poussée", burn.craft);
+      }
+    }
+
+    // Impulsions atteintes
+    while let Some(impulse) = self.impulses.get(self.next)
+      && self.sign * (impulse.time - now) <= EPSILON
+    {
+      self.next += 1;
+      if let Some(i) = index(items, &impulse.craft) {
+        Self::impulse(impulse, self.sign, i, state, items);
+        changed = true;
+      }
+    }
+
+    // La force de poussée est présente dès le début pour les engins à poussée continue,
+    // et change au début ou à la fin d'une poussée
+    for burn in &self.burns {
+      let Some(i) = index(items, &burn.craft) else {
+        continue;
+      };
+      let parameters = &mut state.force_parameters[i];
+      if parameters.thrust.is_none() {
+        parameters.thrust = Some(Thrust { acceleration: 0.0, direction: burn.direction });
+      }
+      changed |= burn.is_active(self.sign, previous) != burn.is_active(self.sign, now);
+    }
+    changed
+  }
+
+  /// Incrément de vitesse de l'engin `i`, limité par l'équation de Tsiolkovski aux ergols disponibles
+  ///
+  /// L'incrément obtenu est enregistré sur l'engin, pour être retiré tel quel en remontant le temps.
+  fn impulse(impulse: &Impulse, sign: f64, i: usize, state: &mut SystemState, items: &mut [CelestItem]) {
+    let local = dominant(&state.masses).and_then(|central| relative(&state.positions, &state.velocities, i, central));
+    let delta_v = match (impulse.frame, local) {
+      (ManeuverFrame::Vnb, Some((r, v))) => frames::vnb_to_inertial(r, v, impulse.delta_v),
+      _ => impulse.delta_v,
+    };
+    let magnitude = delta_v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if magnitude == 0.0 {
+      return;
+    }
+    let mass = items[i].mass;
+    let propellant = items[i].spacecraft.as_ref().map_or(0.0, |craft| craft.propellant);
+
+    let (applied, consumed) = if sign > 0.0 {
+      let needed = mass * (1.0 - (-magnitude / impulse.exhaust_speed).exp());
+      let (scale, consumed) = if needed <= propellant {
+        (1.0, needed)
+      } else {
+        let possible = impulse.exhaust_speed * (mass / (mass - propellant)).ln();
+        tracing::warn!(
+          "⛽ {} : ergols insuffisants, impulsion réduite à {:.1} m/s sur {:.1} m/s",
+          impulse.craft,
+          possible,
+          magnitude
+        );
+        (possible / magnitude, propellant)
+      };
+      let applied = delta_v.map(|x| scale * x);
+      if let Some(craft) = items[i].spacecraft.as_mut() {
+        craft.executed.push(ExecutedImpulse { epoch: impulse.epoch, delta_v: applied, propellant: consumed });
+      }
+      (applied, consumed)
+    } else {
+      // Annulation en remontant le temps : l'incrément obtenu est retiré et les ergols brûlés
+      // restitués, l'impulsion complète à défaut d'exécution enregistrée
+      let executed = items[i].spacecraft.as_mut().and_then(|craft| {
+        let k = craft.executed.iter().rposition(|executed| executed.epoch == impulse.epoch)?;
+        Some(craft.executed.remove(k))
+      });
+      match executed {
+        Some(executed) => (executed.delta_v.map(|x| -x), -executed.propellant),
+        None => (delta_v.map(|x| -x), -mass * ((magnitude / impulse.exhaust_speed).exp() - 1.0)),
+      }
+    };
+
+    let velocity = state.velocities.get(i);
+    state.velocities.set(i, std::array::from_fn(|k| velocity[k] + applied[k]));
+    consume(state, items, i, consumed);
+    tracing::info!(
+      "🚀 {} : impulsion de {:.1} m/s le {}, {:.1} kg d'ergols restants",
+      impulse.craft,
+      sign * applied.iter().map(|x| x * x).sum::<f64>().sqrt(),
+      impulse.epoch,
+      items[i].spacecraft.as_ref().map_or(0.0, |craft| craft.propellant)
+    );
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bll::integrator;
+  use crate::bll::time;
+  use crate::bo::force_parameters::ForceParameters;
+  use crate::bo::spacecraft::Spacecraft;
+  use crate::bo::simulation_options::SimulationOptions;
+  use crate::bo::time_scale::TimeScale;
+
+  /// Sonde isolée : impulsion selon x à 1 h, poussée continue selon y de 2 h à 2 h + 10⁴ s
+  fn probe(start: Epoch) -> Vec<CelestItem> {
+    let hours = |h: f64| start.after(h * 3600.0);
+    let probe = CelestItem {
+      name: "Sonde".into(),
+      mass: 1000.0,
+      radius: 1.0,
+      spacecraft: Some(Spacecraft {
+        propellant: 500.0,
+        maneuvers: vec![
+          Maneuver::Impulsive { epoch: hours(1.0), delta_v: [100.0, 0.0, 0.0], frame: ManeuverFrame::Inertial, isp: 300.0 },
+          Maneuver::Finite {
+            start: hours(2.0),
+            duration: 1e4,
+            thrust: 10.0,
+            isp: 3000.0,
+            direction: ThrustDirection::Inertial([0.0, 1.0, 0.0]),
+          },
+        ],
+        executed: vec![],
+      }),
+      ..Default::default()
+    };
+    // Corps dominant lointain et léger, dont l'attraction est négligeable
+    let anchor = CelestItem { name: "Ancre".into(), mass: 1e6, radius: 1.0, position: [1e15, 0.0, 0.0], ..Default::default() };
+    vec![anchor, probe]
+  }
+
+  /// Intègre `items` pendant `duration` secondes par pas d'une heure, comme `Simulator::run_from`
+  fn run(items: &mut [CelestItem], start: Epoch, duration: f64) {
+    let mut state = SystemState::from_items(items);
+    let mut maneuvers = ManeuverScheduler::new(items, start, duration).unwrap();
+    maneuvers.apply(0.0, 0.0, &mut state, items);
+    let mut integrator = integrator::build(&SimulationOptions::default(), &state);
+    let mut elapsed = 0.0;
+    while (duration - elapsed).abs() > EPSILON {
+      let dt = maneuvers.prepare(elapsed, (duration - elapsed).clamp(-3600.0, 3600.0), &mut state, items);
+      let done = integrator.step(&mut state, dt).unwrap();
+      if maneuvers.apply(elapsed, elapsed + done, &mut state, items) {
+        integrator.reset();
+      }
+      elapsed += done;
+    }
+    state.write_to(items);
+  }
+
+  #[test]
+  fn burns_follow_the_rocket_equation_and_reverse() {
+    let start = time::parse("2000-01-01T12:00:00Z", TimeScale::Utc).unwrap();
+    let mut items = probe(start);
+    run(&mut items, start, 86400.0);
+
+    let after_impulse = 1000.0 * (-100.0 / (300.0 * STANDARD_GRAVITY)).exp();
+    let after_burn = after_impulse - 10.0 / (3000.0 * STANDARD_GRAVITY) * 1e4;
+    let expected = 3000.0 * STANDARD_GRAVITY * (after_impulse / after_burn).ln();
+    let probe = &items[1];
+    assert!((probe.velocity[0] - 100.0).abs() < 1e-9);
+    assert!((probe.velocity[1] / expected - 1.0).abs() < 1e-6, "vy = {}, attendu {expected}", probe.velocity[1]);
+    assert!((probe.mass - after_burn).abs() < 1e-9);
+    assert!((probe.spacecraft.as_ref().unwrap().propellant - (after_burn - 500.0)).abs() < 1e-9);
+
+    // En remontant le temps, les manœuvres sont annulées et les ergols restitués
+    run(&mut items, start.after(86400.0), -86400.0);
+    let probe = &items[1];
+    assert!(probe.velocity.iter().all(|v| v.abs() < 1e-6), "vitesse {:?}", probe.velocity);
+    assert!((probe.spacecraft.as_ref().unwrap().propellant - 500.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn impulses_cut_short_by_propellant_reverse_exactly() {
+    let start = time::parse("2000-01-01T12:00:00Z", TimeScale::Utc).unwrap();
+    let mut items = probe(start);
+    // Dix kilogrammes d'ergols, soit une trentaine des 100 m/s demandés, sans poussée continue
+    let craft = items[1].spacecraft.as_mut().unwrap();
+    craft.propellant = 10.0;
+    craft.maneuvers.truncate(1);
+    run(&mut items, start, 7200.0);
+
+    let possible = 300.0 * STANDARD_GRAVITY * (1000.0 / 990.0_f64).ln();
+    let probe = &items[1];
+    assert!((probe.velocity[0] - possible).abs() < 1e-9, "vx = {}, attendu {possible}", probe.velocity[0]);
+    assert_eq!(probe.spacecraft.as_ref().unwrap().executed.len(), 1);
+
+    // Seul l'incrément obtenu est retiré, et seuls les ergols brûlés sont restitués
+    run(&mut items, start.after(7200.0), -7200.0);
+    let probe = &items[1];
+    assert!(probe.velocity.iter().all(|v| v.abs() < 1e-9), "vitesse {:?}", probe.velocity);
+    assert!((probe.mass - 1000.0).abs() < 1e-9);
+    let craft = probe.spacecraft.as_ref().unwrap();
+    assert!((craft.propellant - 10.0).abs() < 1e-9);
+    assert!(craft.executed.is_empty());
+  }
+  #[test]
+  fn configured_thrust_is_refused_alongside_burns() {
+    let start = time::parse("2000-01-01T12:00:00Z", TimeScale::Utc).unwrap();
+    let thrust = Thrust { acceleration: 1e-3, direction: ThrustDirection::Inertial([1.0, 0.0, 0.0]) };
+    let mut items = probe(start);
+    items[1].forces = Some(ForceParameters { thrust: Some(thrust), ..Default::default() });
+    assert!(ManeuverScheduler::new(&items, start, 86400.0).is_err());
+
+    // Sans poussée continue programmée, la poussée configurée reste admise
+    items[1].spacecraft.as_mut().unwrap().maneuvers.truncate(1);
+    assert!(ManeuverScheduler::new(&items, start, 86400.0).is_ok());
+  }
+}
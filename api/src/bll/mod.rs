@@ -1 +1,16 @@
-pub mod simulator;
\ No newline at end of file
+pub mod collisions;
+pub mod diagnostics;
+pub mod dynamics;
+pub mod ephemeris;
+pub mod events;
+pub mod forces;
+pub mod frames;
+pub mod gravity;
+pub mod integrator;
+pub mod kepler;
+pub mod maneuvers;
+pub mod orbits;
+pub mod simulator;
+pub mod sky;
+pub mod state;
+pub mod time;
\ No newline at end of file
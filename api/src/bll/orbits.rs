@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::bll::gravity::G;
+use crate::bo::celest_item::CelestItem;
+use crate::bo::orbital_elements::{BodyElements, OrbitalElements};
+
+/// Éléments osculateurs de chaque objet relativement au corps `central`, par défaut le plus massif
+///
+/// Chaque objet est rapporté au corps central daté du même instant ; la masse d'une
+/// particule test n'entre pas dans le paramètre gravitationnel.
+pub fn osculating_elements(items: &[CelestItem], central: Option<&str>) -> Result<Vec<BodyElements>> {
+  let central = match central {
+    Some(name) => name.to_string(),
+    None => match items.iter().filter(|item| !item.test_particle).max_by(|a, b| a.mass.total_cmp(&b.mass)) {
+      Some(item) => item.name.clone(),
+      None => return Ok(vec![]),
+    },
+  };
+
+  let centers: HashMap<_, &CelestItem> =
+    items.iter().filter(|item| item.name == central).map(|item| (item.timestamp, item)).collect();
+  if centers.is_empty() {
+    bail!("corps central inconnu : {central}");
+  }
+
+  let elements = items
+    .iter()
+    .filter(|item| item.name != central)
+    .filter_map(|item| {
+      let center = centers.get(&item.timestamp)?;
+      let mass = if item.test_particle { 0.0 } else { item.mass };
+      let position = std::array::from_fn(|k| item.position[k] - center.position[k]);
+      let velocity = std::array::from_fn(|k| item.velocity[k] - center.velocity[k]);
+      Some(BodyElements {
+        name: item.name.clone(),
+        timestamp: item.timestamp,
+        central_body: central.clone(),
+        elements: OrbitalElements::from_state(position, velocity, G * (center.mass + mass)),
+      })
+    })
+    .collect();
+  Ok(elements)
+}
+
+/// Remplace les éléments moyens `orbit` de chaque objet par sa position et sa vitesse
+///
+/// Les corps centraux sont résolus avant leurs satellites, de sorte qu'une lune peut
+/// être décrite relativement à une planète elle-même donnée par ses éléments. Seules les
+/// orbites elliptiques sont acceptées, les éléments moyens n'ayant pas de sens au-delà.
+pub fn resolve_initial_orbits(items: &mut [CelestItem]) -> Result<()> {
+  for item in items.iter() {
+    let Some(orbit) = &item.orbit else { continue };
+    let (a, e) = (orbit.elements.semi_major_axis, orbit.elements.eccentricity);
+    if !(a.is_finite() && a > 0.0 && (0.0..1.0).contains(&e)) {
+      bail!("orbite elliptique attendue pour {} : demi-grand axe positif et excentricité dans [0, 1[", item.name);
+    }
+  }
+
+  loop {
+    let mut pending = 0;
+    let mut resolved = 0;
+    for index in 0..items.len() {
+      let Some(orbit) = items[index].orbit.clone() else { continue };
+      pending += 1;
+      let Some(center) = items.iter().position(|item| item.name == orbit.central_body) else {
+        bail!("corps central inconnu pour {} : {}", items[index].name, orbit.central_body);
+      };
+      if items[center].orbit.is_some() {
+        continue;
+      }
+
+      let mass = if items[index].test_particle { 0.0 } else { items[index].mass };
+      let (position, velocity) = orbit.elements.osculating().to_state(G * (items[center].mass + mass));
+      let (origin, drift) = (items[center].position, items[center].velocity);
+      let item = &mut items[index];
+      item.position = std::array::from_fn(|k| origin[k] + position[k]);
+      item.velocity = std::array::from_fn(|k| drift[k] + velocity[k]);
+      item.orbit = None;
+      resolved += 1;
+    }
+    if pending == resolved {
+      return Ok(());
+    }
+    if resolved == 0 {
+      bail!("dépendance circulaire entre les corps centraux des conditions initiales");
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bo::orbital_elements::{InitialOrbit, MeanElements};
+
+  const AU: f64 = 1.495_978_707e11;
+
+  fn item(name: &str, mass: f64, orbit: Option<InitialOrbit>) -> CelestItem {
+    CelestItem { name: name.into(), mass, radius: 1.0, orbit, ..Default::default() }
+  }
+
+  fn orbit(central_body: &str, semi_major_axis: f64, mean_longitude: f64) -> Option<InitialOrbit> {
+    Some(InitialOrbit {
+      central_body: central_body.into(),
+      elements: MeanElements {
+        semi_major_axis,
+        eccentricity: 0.01671123,
+        inclination: -0.00001531,
+        mean_longitude,
+        longitude_of_perihelion: 102.93768193,
+        longitude_of_ascending_node: 0.0,
+      },
+    })
+  }
+
+  #[test]
+  fn mean_elements_place_earth_at_its_j2000_position() {
+    // La lune est déclarée avant sa planète pour exercer l'ordre de résolution
+    let mut items = vec![
+      item("Lune", 7.342e22, orbit("Terre", 3.844e8, 0.0)),
+      item("Soleil", 1.9885e30, None),
+      item("Terre", 6.0458e24, orbit("Soleil", 1.00000261 * AU, 100.46457166)),
+    ];
+    resolve_initial_orbits(&mut items).unwrap();
+
+    // Barycentre Terre-Lune au 1er janvier 2000 à 12 h TT, d'après les éphémérides JPL
+    let earth = items[2].position.map(|x| x / AU);
+    assert!((earth[0] + 0.1771).abs() < 2e-3 && (earth[1] - 0.9672).abs() < 2e-3 && earth[2].abs() < 1e-5, "{earth:?}");
+    let distance = (0..3).map(|k| (items[0].position[k] - items[2].position[k]).powi(2)).sum::<f64>().sqrt();
+    assert!((distance / 3.844e8 - 1.0).abs() < 0.02);
+    assert!(items.iter().all(|item| item.orbit.is_none()));
+
+    let mut cycle = vec![item("A", 1.0, orbit("B", AU, 0.0)), item("B", 1.0, orbit("A", AU, 0.0))];
+    assert!(resolve_initial_orbits(&mut cycle).is_err());
+
+    // Orbites ouvertes ou dégénérées refusées
+    for (semi_major_axis, eccentricity) in [(AU, 1.0), (AU, 1.5), (AU, -0.1), (-AU, 0.5), (f64::NAN, 0.1)] {
+      let mut open = orbit("Soleil", semi_major_axis, 0.0);
+      if let Some(open) = &mut open {
+        open.elements.eccentricity = eccentricity;
+      }
+      let mut items = vec![item("Soleil", 1.9885e30, None), item("Comète", 0.0, open)];
+      assert!(resolve_initial_orbits(&mut items).is_err(), "{semi_major_axis} {eccentricity}");
+    }
+  }
+}
@@ -1,143 +1,484 @@
+use crate::bll::collisions::CollisionHandler;
+use crate::bll::diagnostics;
+use crate::bll::ephemeris::Ephemeris;
+use crate::bll::events::EventDetector;
+use crate::bll::frames;
+use crate::bll::integrator;
+use crate::bll::maneuvers::ManeuverScheduler;
+use crate::bll::orbits;
+use crate::bll::state::SystemState;
+use crate::bll::time;
 use crate::bo::celest_item::CelestItem;
+use crate::bo::diagnostics::Diagnostics;
+use crate::bo::epoch::Epoch;
+use crate::bo::orbital_event::OrbitalEvent;
+use crate::bo::simulation_options::SimulationOptions;
+use crate::bo::simulation_result::{IntegrationStats, SimulationResult};
+use crate::bo::time_scale::TimeScale;
 use crate::dal::celest_item_dao::CelestItemDAO;
 use crate::dal::dao_factory::DAOFactory;
-use anyhow::Context;
-use chrono::{DateTime, Utc};
-use rayon::prelude::*;
+use actix_web::web;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
 use std::time::Instant;
 use std::sync::Arc;
 
+// Pas par défaut des intégrateurs à pas fixe (s), allongé au-delà de `DEFAULT_STEP_COUNT` pas
+// sans dépasser `MAX_DEFAULT_TIME_STEP`
+const DEFAULT_TIME_STEP: f64 = 3600.0;
+const DEFAULT_STEP_COUNT: f64 = 10_000.0;
+const MAX_DEFAULT_TIME_STEP: f64 = 86_400.0;
+// Nombre de pas au-delà duquel une intégration est abandonnée plutôt que de bloquer le serveur
+const MAX_STEPS: u64 = 1_000_000;
+// Version du format des résultats enregistrés, à incrémenter lorsqu'ils changent de sens
+const CACHE_VERSION: u32 = 1;
+
 pub struct Simulator {
   dao: Arc<CelestItemDAO>,
   pub celest_items: Vec<CelestItem>,
+  epoch: Epoch,
+  dataset: String,   // empreinte des conditions initiales, clé des résultats enregistrés
 }
 
 impl Simulator {
-  const REFERENCE_DATE: &'static str = "2000-01-01T12:00:00Z";
-
   pub async fn new(factory: Arc<DAOFactory>, path: &str) -> Self {
     let dao = factory.celest_item_dao();
-    let celest_items = factory.celest_item_dao().load_celest_items(path).await.context(
+    let mut celest_items = factory.celest_item_dao().load_celest_items(path).await.context(
       format!("Erreur lors du chargement des objets célestes depuis le fichier : {}", path),
     ).unwrap();
+    orbits::resolve_initial_orbits(&mut celest_items)
+      .context("Conditions initiales orbitales invalides")
+      .unwrap();
+    frames::to_barycentric(&mut celest_items);
+    let epoch = Self::initial_epoch(&celest_items).context("Date des conditions initiales invalide").unwrap();
+    let dataset = Self::dataset(&celest_items);
 
     Simulator {
       dao,
       celest_items,
+      epoch,
+      dataset,
     }
   }
 
-  pub fn run(&self, target_date: DateTime<Utc>) -> Vec<CelestItem> {
-    let start = Instant::now();
-    const G: f64 = 6.67430e-11;
-    let reference_date = DateTime::parse_from_rfc3339(Self::REFERENCE_DATE)
-      .expect("Date de référence invalide")
-      .with_timezone(&Utc);
-    let delta_seconds = target_date.signed_duration_since(reference_date).num_seconds() as f64;
-
-    // Pas de temps initial (1h)
-    let mut dt = 3600.0;
-    let mut steps = (delta_seconds / dt).abs() as usize;
-    let max_steps = 10_000;
-    if steps > max_steps {
-      dt = delta_seconds.abs() / max_steps as f64;
-      steps = max_steps;
+  /// Empreinte des conditions initiales résolues (FNV-1a 64 bits de leur JSON)
+  ///
+  /// Les résultats enregistrés sont rangés sous cette empreinte : modifier le fichier des
+  /// conditions initiales, ou en désigner un autre, n'en ressert jamais d'anciens.
+  fn dataset(items: &[CelestItem]) -> String {
+    let bytes = serde_json::to_vec(items).expect("objets célestes sérialisables");
+    let hash = bytes.iter().fold(0xcbf29ce484222325_u64, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3));
+    format!("v{CACHE_VERSION}-{hash:016x}")
+  }
+
+  /// Date commune des conditions initiales, la date de référence J2000 si elles n'en portent pas
+  fn initial_epoch(items: &[CelestItem]) -> Result<Epoch> {
+    let mut dates = items.iter().filter_map(|item| item.timestamp);
+    let Some(epoch) = dates.next() else {
+      return Ok(Self::reference_date());
+    };
+    if dates.any(|date| date != epoch) {
+      bail!("les objets célestes doivent tous être datés de la même époque");
     }
+    Ok(epoch)
+  }
+
+  /// Époque J2000, le 1er janvier 2000 à 12 h TT
+  fn reference_date() -> Epoch {
+    time::from_julian_day(time::J2000_JULIAN_DAY, TimeScale::Tt).expect("J2000 représentable")
+  }
+
+  /// Intègre depuis les conditions initiales, datées de leur époque
+  pub async fn run(&self, target_date: Epoch, options: &SimulationOptions) -> Result<SimulationResult> {
+    Self::integrate(self.celest_items.clone(), self.epoch, target_date, options).await
+  }
+
+  /// Intègre comme `run_from` sur le pool de threads bloquants, sans occuper le thread qui sert la requête
+  async fn integrate(
+    initial: Vec<CelestItem>,
+    start_date: Epoch,
+    target_date: Epoch,
+    options: &SimulationOptions,
+  ) -> Result<SimulationResult> {
+    let options = options.clone();
+    web::block(move || Self::run_from(&initial, start_date, target_date, &options)).await?
+  }
+
+  /// Intègre l'état `initial`, daté de `start_date`, jusqu'à `target_date`
+  pub fn run_from(
+    initial: &[CelestItem],
+    start_date: Epoch,
+    target_date: Epoch,
+    options: &SimulationOptions,
+  ) -> Result<SimulationResult> {
+    let start = Instant::now();
+    // Durée en temps dynamique, secondes intercalaires comprises
+    let delta_seconds = target_date.seconds_since(start_date);
+
+    // Corps massifs en tête : les particules test, en fin d'état, ne sont pas sources de gravitation
+    let (massive, particles): (Vec<CelestItem>, Vec<CelestItem>) =
+      initial.iter().cloned().partition(|item| !item.test_particle);
+    let mut celest_items = [massive.as_slice(), &particles].concat();
+    let mut state = SystemState::from_items(&celest_items);
+    let mut maneuvers = ManeuverScheduler::new(&celest_items, start_date, delta_seconds)?;
+    maneuvers.apply(0.0, 0.0, &mut state, &mut celest_items);
+    let mut integrator = integrator::build(options, &state);
+    let mut collisions = CollisionHandler::new(options.collisions);
+    let mut events = EventDetector::new(options.events.as_ref(), &celest_items)?;
+    let mut accepted_steps = 0;
+    let mut elapsed: f64 = 0.0;
+
+    // Un pas d'intégration, arrêté à la prochaine manœuvre, suivi de la recherche des événements
+    // et du traitement des collisions et des manœuvres ; retourne la durée intégrée
+    let mut advance = |elapsed: f64, dt: f64| -> Result<f64> {
+      let dt = maneuvers.prepare(elapsed, events.bound(dt), &mut state, &celest_items);
+      events.begin(&state, &celest_items);
+      let done = integrator.step(&mut state, dt)?;
+      events.detect(start_date.after(elapsed), done, &state, &celest_items);
+      let date = start_date.after(elapsed + done);
+      let mut changed = collisions.resolve(&mut state, &mut celest_items, date)?;
+      changed |= maneuvers.apply(elapsed, elapsed + done, &mut state, &mut celest_items);
+      if changed {
+        integrator.reset();
+      }
+      Ok(done)
+    };
 
-    let sign = if delta_seconds >= 0.0 { 1.0 } else { -1.0 };
-    let mut state: Vec<CelestItem> = self.celest_items.to_vec();
-
-    for _ in 0..steps {
-      let shared_state = Arc::new(state.clone());
-
-      // 1. Calcul des accélérations en parallèle
-      let accelerations: Vec<[f64; 3]> = (0..shared_state.len())
-        .into_par_iter()
-        .map(|i| {
-          let mut acc = [0.0; 3];
-          for (j, other) in shared_state.iter().enumerate() {
-            if i == j {
-              continue;
-            }
-
-            let self_p = &shared_state[i];
-            let dx = other.position[0] - self_p.position[0];
-            let dy = other.position[1] - self_p.position[1];
-            let dz = other.position[2] - self_p.position[2];
-
-            let r_squared = dx * dx + dy * dy + dz * dz;
-            let r = r_squared.sqrt();
-
-            if r < 1e3 {
-              continue;
-            }
-
-            let force_mag = G * other.mass / r_squared;
-            acc[0] += force_mag * dx / r;
-            acc[1] += force_mag * dy / r;
-            acc[2] += force_mag * dz / r;
-          }
-          acc
-        })
-        .collect();
-
-      // 2. Mise à jour des vitesses et positions en parallèle
-      state
-        .par_iter_mut()
-        .zip(accelerations.par_iter())
-        .for_each(|(celestItem, acc)| {
-          if celestItem.name != "Soleil" {
-            for k in 0..3 {
-              celestItem.velocity[k] += sign * acc[k] * dt;
-              celestItem.position[k] += sign * celestItem.velocity[k] * dt;
-            }
-          }
-        });
+    if options.integrator.is_adaptive() || options.integrator.is_analytic() {
+      // Le pas est piloté par la tolérance, ou inutile en propagation analytique : on intègre jusqu'à la date cible
+      while elapsed.abs() < delta_seconds.abs() {
+        if accepted_steps >= MAX_STEPS {
+          bail!("date cible non atteinte en {MAX_STEPS} pas : tolérance trop stricte ou intervalle trop long");
+        }
+        elapsed += advance(elapsed, delta_seconds - elapsed)?;
+        accepted_steps += 1;
+      }
+    } else {
+      // Pas imposé par la requête, à défaut une heure allongée jusqu'à un jour pour s'en tenir à
+      // `DEFAULT_STEP_COUNT` pas ; le dernier pas, comme ceux découpés aux dates des manœuvres,
+      // couvre exactement le reste de l'intervalle pour que l'état corresponde à la date cible
+      let time_step = options
+        .time_step
+        .unwrap_or_else(|| (delta_seconds.abs() / DEFAULT_STEP_COUNT).clamp(DEFAULT_TIME_STEP, MAX_DEFAULT_TIME_STEP));
+      if (delta_seconds / time_step).abs().ceil() > MAX_STEPS as f64 {
+        match options.time_step {
+          Some(_) => bail!("plus de {MAX_STEPS} pas de {time_step} s nécessaires : allonger time_step"),
+          // Des pas plus longs qu'un jour rendraient le résultat dénué de sens sans le signaler
+          None => bail!(
+            "intervalle trop long pour {MAX_STEPS} pas d'un jour : choisir kepler, ou wisdom_holman avec un time_step adapté"
+          ),
+        }
+      }
+      let sign = if delta_seconds >= 0.0 { 1.0 } else { -1.0 };
+      while elapsed.abs() < delta_seconds.abs() {
+        let dt = (delta_seconds.abs() - elapsed.abs()).min(time_step.abs());
+        elapsed += advance(elapsed, sign * dt)?;
+        accepted_steps += 1;
+      }
     }
-    
-    // 3. Ajout du timestamp à chaque objet simulé
-    for item in state.iter_mut() {
+
+    // Retour aux objets célestes et ajout du timestamp à chaque objet simulé
+    state.write_to(&mut celest_items);
+    for item in celest_items.iter_mut() {
       item.timestamp = Some(target_date);
     }
 
     let duration = start.elapsed();
+    let stats = IntegrationStats {
+      integrator: options.integrator,
+      accepted_steps,
+      rejected_steps: integrator.rejected_steps(),
+    };
     tracing::info!(
-      "⏱️ Simulation terminée en {} secondes",
+      "⏱️ Simulation ({:?}, {} pas acceptés, {} rejetés) terminée en {} secondes",
+      stats.integrator,
+      stats.accepted_steps,
+      stats.rejected_steps,
       duration.as_secs_f64()
     );
 
-    state
+    let split = celest_items.iter().position(|item| item.test_particle).unwrap_or(celest_items.len());
+    let test_particles = celest_items.split_off(split);
+    let diagnostics = diagnostics::diagnose(&massive, &celest_items, elapsed);
+    tracing::info!(
+      "⚖️ Erreurs relatives : énergie {:e}, quantité de mouvement {:e}, moment cinétique {:e}, centre de masse {:e}",
+      diagnostics.energy_error,
+      diagnostics.linear_momentum_error,
+      diagnostics.angular_momentum_error,
+      diagnostics.center_of_mass_error
+    );
+
+    Ok(SimulationResult {
+      celest_items,
+      test_particles,
+      stats: Some(stats),
+      diagnostics: Some(diagnostics),
+      collisions: collisions.events,
+      events: events.into_events(),
+      orbital_elements: vec![],
+    })
   }
 
-  pub async fn load_or_compute(&self, target_date: DateTime<Utc>) -> Vec<CelestItem> {
-    if let Ok(cached) = self.dao.find_by_date(target_date).await {
-      if !cached.is_empty() {
-        return cached;
-      }
+  pub async fn load_or_compute(&self, target_date: Epoch, options: &SimulationOptions) -> Result<SimulationResult> {
+    // Le cache ne contient que des résultats calculés avec les options par défaut
+    let cacheable = *options == SimulationOptions::default();
+
+    if cacheable
+      && let Ok(cached) = self.dao.find_by_date(&self.dataset, target_date).await
+      && !cached.is_empty()
+    {
+      let diagnostics = self.cached_diagnostics(target_date).await;
+      return Ok(Self::from_cache(cached, diagnostics));
     }
 
-    // let result: Vec<CelestItem> = [].to_vec();
-    let result = self.run(target_date);
-    self.dao.save_many(&result).await.unwrap_or_else(|err| {
+    let result = self.compute(target_date, options).await?;
+    if cacheable {
+      self.save(&result).await;
+    }
+
+    Ok(result)
+  }
+
+  pub async fn get_data(&self, start: Epoch, stop: Epoch, options: &SimulationOptions) -> Result<SimulationResult> {
+    let cacheable = *options == SimulationOptions::default();
+
+    if cacheable
+      && let Ok(cached) = self.dao.find_by_dates(&self.dataset, start, stop).await
+      && !cached.is_empty()
+    {
+      // Diagnostics de l'instantané renvoyé, enregistrés avec lui
+      let diagnostics = match cached.first().and_then(|item| item.timestamp) {
+        Some(date) => self.cached_diagnostics(date).await,
+        None => None,
+      };
+      return Ok(Self::from_cache(cached, diagnostics));
+    }
+
+    let result = self.compute(start, options).await?;
+    if cacheable {
+      self.save(&result).await;
+    }
+
+    Ok(result)
+  }
+
+  /// Événements orbitaux de `from` à `to`, dans l'ordre chronologique
+  ///
+  /// L'état de départ est lu en cache ou intégré sans détection, puis l'intervalle est
+  /// parcouru avec les options `options`, qui portent les événements recherchés.
+  pub async fn find_events(&self, from: Epoch, to: Epoch, options: &SimulationOptions) -> Result<Vec<OrbitalEvent>> {
+    let initial = self.load_or_compute(from, &SimulationOptions { events: None, ..options.clone() }).await?;
+    let items = [initial.celest_items.as_slice(), &initial.test_particles].concat();
+    Ok(Self::integrate(items, from, to, options).await?.events)
+  }
+
+  /// Intègre depuis l'instantané enregistré le plus proche, à défaut depuis la date de référence
+  async fn compute(&self, target_date: Epoch, options: &SimulationOptions) -> Result<SimulationResult> {
+    match self.nearest_snapshot(target_date, options).await {
+      Some((snapshot_date, items)) => self.resume(items, snapshot_date, target_date, options).await,
+      None => self.run(target_date, options).await,
+    }
+  }
+
+  /// Intègre depuis l'instantané `items` daté de `snapshot_date`
+  ///
+  /// Le bilan de conservation porte sur toute la durée écoulée depuis les conditions
+  /// initiales, et non sur le seul intervalle repris.
+  async fn resume(
+    &self,
+    items: Vec<CelestItem>,
+    snapshot_date: Epoch,
+    target_date: Epoch,
+    options: &SimulationOptions,
+  ) -> Result<SimulationResult> {
+    tracing::info!("⏩ Reprise de la simulation depuis l'instantané du {}", snapshot_date);
+    let mut result = Self::integrate(items, snapshot_date, target_date, options).await?;
+    let initial: Vec<CelestItem> = self.celest_items.iter().filter(|item| !item.test_particle).cloned().collect();
+    result.diagnostics = Some(diagnostics::diagnose(&initial, &result.celest_items, target_date.seconds_since(self.epoch)));
+    Ok(result)
+  }
+
+  /// Instantané utilisable le plus proche de `target_date`, s'il l'est davantage que les conditions initiales
+  async fn nearest_snapshot(
+    &self,
+    target_date: Epoch,
+    options: &SimulationOptions,
+  ) -> Option<(Epoch, Vec<CelestItem>)> {
+    // Les instantanés ont été calculés avec les options par défaut
+    if *options != SimulationOptions::default() {
+      return None;
+    }
+
+    let snapshot = self.dao.find_nearest_by_date(&self.dataset, target_date).await.unwrap_or_else(|err| {
+      eprintln!("Erreur lors de la recherche d'un instantané : {err}");
+      vec![]
+    });
+    let snapshot_date = snapshot.first().and_then(|item| item.timestamp)?;
+    let distance = |date: Epoch| target_date.seconds_since(date).abs();
+    if distance(snapshot_date) >= distance(self.epoch) {
+      return None;
+    }
+
+    // Même ensemble d'objets, remis dans l'ordre des conditions initiales
+    let items: Vec<CelestItem> = self
+      .celest_items
+      .iter()
+      .map(|item| snapshot.iter().find(|other| other.name == item.name && other.timestamp == Some(snapshot_date)).cloned())
+      .collect::<Option<_>>()?;
+    if snapshot.len() != items.len() {
+      return None;
+    }
+    Some((snapshot_date, items))
+  }
+
+  /// Diagnostics enregistrés pour la date `date`, s'il y en a
+  async fn cached_diagnostics(&self, date: Epoch) -> Option<Diagnostics> {
+    self.dao.find_diagnostics_by_date(&self.dataset, date).await.unwrap_or_else(|err| {
+      eprintln!("Erreur lors de la lecture des diagnostics en cache : {err}");
+      None
+    })
+  }
+
+  /// Résultat reconstitué depuis le cache, où corps massifs et particules test sont enregistrés ensemble
+  fn from_cache(items: Vec<CelestItem>, diagnostics: Option<Diagnostics>) -> SimulationResult {
+    let (test_particles, celest_items) = items.into_iter().partition(|item| item.test_particle);
+    SimulationResult { celest_items, test_particles, stats: None, diagnostics, collisions: vec![], events: vec![], orbital_elements: vec![] }
+  }
+
+  /// Sauvegarde les objets simulés et leurs diagnostics dans le cache
+  async fn save(&self, result: &SimulationResult) {
+    self.dao.save_many(&self.dataset, &[result.celest_items.as_slice(), &result.test_particles].concat()).await.unwrap_or_else(|err| {
       eprintln!("Erreur lors de la sauvegarde dans le cache : {err}");
     });
+    if let Some(diagnostics) = &result.diagnostics {
+      self.dao.save_diagnostics(&self.dataset, diagnostics).await.unwrap_or_else(|err| {
+        eprintln!("Erreur lors de la sauvegarde des diagnostics dans le cache : {err}");
+      });
+    }
+  }
+}
+
+#[async_trait]
+impl Ephemeris for Simulator {
+  async fn states_at(&self, date: Epoch, options: &SimulationOptions) -> Result<SimulationResult> {
+    self.load_or_compute(date, options).await
+  }
+}
+
 
-    result
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bo::simulation_options::{EventOptions, IntegratorKind};
+
+  /// Simulateur des conditions initiales livrées, sans Mongo même configuré
+  async fn simulator() -> Simulator {
+    Simulator::new(Arc::new(DAOFactory::without_mongo()), "data/celest_items.json").await
+  }
+
+  fn distance(a: &CelestItem, b: &CelestItem) -> f64 {
+    (0..3).map(|k| (a.position[k] - b.position[k]).powi(2)).sum::<f64>().sqrt()
   }
 
-  pub async fn get_data(&self, start: DateTime<Utc>, stop: DateTime<Utc>) -> Vec<CelestItem> {
-    if let Ok(cached) = self.dao.find_by_dates(start, stop).await {
-      if !cached.is_empty() {
-        return cached;
+  #[tokio::test]
+  async fn fixed_steps_reach_the_target_date_exactly() {
+    let simulator = simulator().await;
+    let start = simulator.epoch;
+    let default = SimulationOptions::default();
+    let fine = SimulationOptions { time_step: Some(60.0), ..Default::default() };
+
+    // Une heure et demie, puis moins d'une heure : le reste du dernier pas est intégré
+    for duration in [5400.0, 1800.0] {
+      let target = start.after(duration);
+      let coarse = simulator.run(target, &default).await.unwrap();
+      let reference = simulator.run(target, &fine).await.unwrap();
+      for (item, expected) in coarse.celest_items.iter().zip(&reference.celest_items) {
+        assert!(distance(item, expected) < 1e3, "{} à {duration} s : {} m", item.name, distance(item, expected));
       }
+      let earth = |result: &SimulationResult| result.celest_items.iter().find(|item| item.name == "Terre").cloned().unwrap();
+      let initial = simulator.celest_items.iter().find(|item| item.name == "Terre").unwrap();
+      assert!(distance(&earth(&coarse), initial) > 0.9 * 29_000.0 * duration);
     }
 
-    // let result: Vec<CelestItem> = [].to_vec();
-    let result = self.run(start);
-    self.dao.save_many(&result).await.unwrap_or_else(|err| {
-      eprintln!("Erreur lors de la sauvegarde dans le cache : {err}");
-    });
+    // Reprendre depuis un état intermédiaire redonne le même état que l'intégration d'un seul tenant
+    let options = SimulationOptions { integrator: IntegratorKind::Leapfrog, time_step: Some(3600.0), ..Default::default() };
+    let middle = simulator.run(start.after(5400.0), &options).await.unwrap();
+    let resumed = Simulator::run_from(&middle.celest_items, start.after(5400.0), start.after(9000.0), &options).unwrap();
+    let direct = simulator.run(start.after(9000.0), &options).await.unwrap();
+    for (item, expected) in resumed.celest_items.iter().zip(&direct.celest_items) {
+      assert!(distance(item, expected) < 1e3, "{} : {} m", item.name, distance(item, expected));
+    }
+  }
+
+  #[tokio::test]
+  async fn imposed_steps_are_bounded() {
+    let simulator = simulator().await;
+    assert!(SimulationOptions { time_step: Some(1e-9), ..Default::default() }.validate().is_err());
+
+    // Un an par pas d'une seconde dépasse le nombre de pas admis : refus immédiat, sans intégrer
+    let options = SimulationOptions { time_step: Some(1.0), ..Default::default() };
+    assert!(options.validate().is_ok());
+    let error = simulator.run(simulator.epoch.after(3.2e7), &options).await.unwrap_err();
+    assert!(error.to_string().contains("time_step"), "{error}");
+  }
 
-    result
+  #[tokio::test]
+  async fn deep_time_needs_an_integrator_suited_to_it() {
+    let simulator = simulator().await;
+    // Un million d'années : refusé au pas fixe par défaut, propagé analytiquement par kepler
+    let target = simulator.epoch.after(1e6 * 365.25 * 86_400.0);
+    let error = simulator.run(target, &SimulationOptions::default()).await.unwrap_err();
+    assert!(error.to_string().contains("kepler"), "{error}");
+    let kepler = SimulationOptions { integrator: IntegratorKind::Kepler, ..Default::default() };
+    let result = simulator.run(target, &kepler).await.unwrap();
+    assert!(result.celest_items.iter().all(|item| item.position.iter().all(|x| x.is_finite())));
   }
-}
\ No newline at end of file
+
+  #[tokio::test]
+  async fn cached_results_are_keyed_by_the_initial_conditions() {
+    let simulator = simulator().await;
+    let dataset = Simulator::dataset(&simulator.celest_items);
+    assert_eq!(dataset, simulator.dataset);
+    assert!(dataset.starts_with(&format!("v{CACHE_VERSION}-")));
+
+    // La moindre retouche d'une condition initiale change de jeu de données
+    let mut items = simulator.celest_items.clone();
+    items[1].velocity[0] += 1e-3;
+    assert_ne!(Simulator::dataset(&items), dataset);
+    assert_eq!(Simulator::dataset(&simulator.celest_items.clone()), dataset);
+  }
+
+  #[tokio::test]
+  async fn events_are_found_in_ranges_shorter_than_a_step() {
+    let simulator = simulator().await;
+    let options = SimulationOptions { events: Some(EventOptions::default()), ..Default::default() };
+    let start = simulator.epoch;
+    let events = simulator.find_events(start, start.after(60.0 * 86_400.0), &options).await.unwrap();
+    assert!(!events.is_empty());
+
+    // Quarante minutes autour du premier événement : moins d'un pas par défaut, même date
+    let first = &events[0];
+    let window = simulator.find_events(first.date.after(-1200.0), first.date.after(1200.0), &options).await.unwrap();
+    let found = window.iter().find(|event| event.kind == first.kind && event.bodies == first.bodies).unwrap();
+    assert!(found.date.seconds_since(first.date).abs() < 1.0, "{} / {}", found.date, first.date);
+  }
+  #[tokio::test]
+  async fn resumed_runs_report_diagnostics_since_the_initial_conditions() {
+    let simulator = simulator().await;
+    let options = SimulationOptions::default();
+    let (middle, target) = (simulator.epoch.after(30.0 * 86_400.0), simulator.epoch.after(60.0 * 86_400.0));
+
+    // Reprise depuis l'état du trentième jour, aux mêmes pas qu'une intégration d'un seul tenant
+    let snapshot = simulator.run(middle, &options).await.unwrap();
+    let items = [snapshot.celest_items.as_slice(), &snapshot.test_particles].concat();
+    let resumed = simulator.resume(items, middle, target, &options).await.unwrap().diagnostics.unwrap();
+    let direct = simulator.run(target, &options).await.unwrap().diagnostics.unwrap();
+
+    assert_eq!(resumed.start, direct.start);
+    let close = |a: f64, b: f64| (a - b).abs() <= 1e-6 * a.abs().max(b.abs()) + 1e-15;
+    assert!(close(resumed.energy_error, direct.energy_error), "{} / {}", resumed.energy_error, direct.energy_error);
+    assert!(close(resumed.angular_momentum_error, direct.angular_momentum_error), "{resumed:?} / {direct:?}");
+    assert!(close(resumed.linear_momentum_error, direct.linear_momentum_error), "{resumed:?} / {direct:?}");
+    assert!(close(resumed.center_of_mass_error, direct.center_of_mass_error), "{resumed:?} / {direct:?}");
+  }
+}
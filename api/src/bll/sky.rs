@@ -0,0 +1,359 @@
+use actix_web::web;
+use anyhow::{bail, Context, Result};
+
+use crate::bll::ephemeris::vsop87;
+use crate::bll::events::{self, Segment};
+use crate::bll::simulator::Simulator;
+use crate::bo::celest_item::CelestItem;
+use crate::bo::epoch::Epoch;
+use crate::bo::simulation_options::SimulationOptions;
+use crate::bo::sky_event::{SkyEvent, SkyEventKind, SkySearch};
+
+// Demi-largeur (s) de l'intervalle réintégré autour d'un événement pour le dater finement
+const REFINEMENT: f64 = 600.0;
+
+/// Position et vitesse d'un objet relativement à l'observateur
+type Relative = ([f64; 3], [f64; 3]);
+
+/// Ciel vu depuis l'observateur à un instant donné
+struct View {
+  names: Vec<String>,
+  sun: Relative,
+  bodies: Vec<Relative>,
+}
+
+/// Grandeur dont le changement de signe signale un événement
+#[derive(Clone, Copy)]
+enum Search {
+  /// Sinus de l'écart en longitude écliptique entre l'objet et le Soleil
+  Longitude(usize),
+  /// Dérivée du cosinus de l'élongation de l'objet
+  Elongation(usize),
+  /// Dérivée du cosinus de la séparation entre deux objets
+  Pair(usize, usize),
+}
+
+/// Mouvement apparent des objets entre deux vues, interpolé comme en `events`
+struct Interval {
+  start: Epoch,
+  duration: f64,
+  sun: Segment,
+  bodies: Vec<Segment>,
+}
+
+impl Interval {
+  /// `None` lorsque les objets diffèrent d'une vue à l'autre, après une fusion
+  fn new(start: Epoch, end: Epoch, a: &View, b: &View) -> Option<Self> {
+    if a.names != b.names {
+      return None;
+    }
+    let duration = end.seconds_since(start);
+    Some(Interval {
+      start,
+      duration,
+      sun: Segment::new(a.sun, b.sun, duration),
+      bodies: a.bodies.iter().zip(&b.bodies).map(|(&a, &b)| Segment::new(a, b, duration)).collect(),
+    })
+  }
+
+  fn value(&self, search: Search, s: f64) -> f64 {
+    match search {
+      Search::Longitude(i) => longitude_sine(self.sun.at(s).0, self.bodies[i].at(s).0),
+      Search::Elongation(i) => direction_rate(self.sun.at(s), self.bodies[i].at(s)),
+      Search::Pair(i, j) => direction_rate(self.bodies[i].at(s), self.bodies[j].at(s)),
+    }
+  }
+
+  fn crossing(&self, search: Search) -> Option<(f64, bool)> {
+    events::root(self.duration, |s| self.value(search, s))
+  }
+
+  /// Événement correspondant au changement de signe à la fraction `s` de l'intervalle, s'il
+  /// en est un : un extremum de l'élongation ou de la séparation peut aussi être un minimum
+  fn event(&self, search: Search, names: &[String], s: f64, rising: bool, max_separation: f64) -> Option<SkyEvent> {
+    let sun = self.sun.at(s).0;
+    let (kind, bodies, separation) = match search {
+      Search::Longitude(i) => {
+        let body = self.bodies[i].at(s).0;
+        let kind = if longitude_cosine(sun, body) < 0.0 {
+          SkyEventKind::Opposition
+        } else if norm(body) < norm(sun) {
+          SkyEventKind::InferiorConjunction
+        } else {
+          SkyEventKind::SuperiorConjunction
+        };
+        (kind, vec![names[i].clone()], angle(sun, body))
+      }
+      Search::Elongation(i) => {
+        let body = self.bodies[i].at(s).0;
+        // Seul un objet plus proche du Soleil que l'observateur atteint une élongation maximale
+        // hors de l'opposition
+        if !rising || norm(difference(body, sun)) >= norm(sun) {
+          return None;
+        }
+        let kind = if longitude_sine(sun, body) > 0.0 {
+          SkyEventKind::GreatestEasternElongation
+        } else {
+          SkyEventKind::GreatestWesternElongation
+        };
+        (kind, vec![names[i].clone()], angle(sun, body))
+      }
+      Search::Pair(i, j) => {
+        let separation = angle(self.bodies[i].at(s).0, self.bodies[j].at(s).0);
+        if rising || separation > max_separation {
+          return None;
+        }
+        (SkyEventKind::Grouping, vec![names[i].clone(), names[j].clone()], separation)
+      }
+    };
+    Some(SkyEvent { date: self.start.after(s * self.duration), kind, bodies, separation })
+  }
+}
+
+/// Événements célestes vus depuis `search.observer` sur l'intervalle [from, to]
+///
+/// Le ciel est échantillonné tous les `search.sample_step` secondes en prolongeant la
+/// simulation d'un instantané au suivant, depuis l'état calculé à la date `from`.
+pub async fn find_sky_events(
+  simulator: &Simulator,
+  from: Epoch,
+  to: Epoch,
+  search: &SkySearch,
+  options: &SimulationOptions,
+) -> Result<Vec<SkyEvent>> {
+  let options = SimulationOptions { events: None, ..options.clone() };
+  let initial = simulator.load_or_compute(from, &options).await?;
+  let items = [initial.celest_items.as_slice(), &initial.test_particles].concat();
+  let search = search.clone();
+  // Les intégrations successives occupent le pool de threads bloquants, pas celui de la requête
+  web::block(move || {
+    scan(from, to, &search, items, |items, start, end| {
+      let result = Simulator::run_from(items, start, end, &options)?;
+      Ok([result.celest_items.as_slice(), &result.test_particles].concat())
+    })
+  })
+  .await?
+}
+
+/// Recherche des événements à partir des objets `items` datés de `from`, `advance` intégrant
+/// des objets d'une date à une autre
+///
+/// Chaque événement repéré entre deux instantanés est ensuite daté sur un intervalle de
+/// ±`REFINEMENT` secondes réintégré autour de sa date approchée.
+fn scan(
+  from: Epoch,
+  to: Epoch,
+  search: &SkySearch,
+  items: Vec<CelestItem>,
+  mut advance: impl FnMut(&[CelestItem], Epoch, Epoch) -> Result<Vec<CelestItem>>,
+) -> Result<Vec<SkyEvent>> {
+  let mut events = Vec::new();
+  let (mut date, mut items) = (from, items);
+  let mut current = view(&items, &search.observer)?;
+
+  while date < to {
+    let next = date.after(search.sample_step).min(to);
+    let next_items = advance(&items, date, next)?;
+    let next_view = view(&next_items, &search.observer)?;
+
+    if let Some(interval) = Interval::new(date, next, &current, &next_view) {
+      for candidate in searches(current.names.len()) {
+        let Some((s, rising)) = interval.crossing(candidate) else { continue };
+        let Some(approximate) = interval.event(candidate, &current.names, s, rising, search.max_separation) else {
+          continue;
+        };
+
+        let window = (approximate.date.after(-REFINEMENT).max(date), approximate.date.after(REFINEMENT).min(next));
+        let before = if window.0 > date { advance(&items, date, window.0)? } else { items.clone() };
+        let after = advance(&before, window.0, window.1)?;
+        let refined = Interval::new(window.0, window.1, &view(&before, &search.observer)?, &view(&after, &search.observer)?);
+        match refined.as_ref().and_then(|r| r.crossing(candidate).map(|(s, rising)| (r, s, rising))) {
+          Some((r, s, rising)) => events.extend(r.event(candidate, &current.names, s, rising, search.max_separation)),
+          None => events.push(approximate),
+        }
+      }
+    }
+
+    (date, items, current) = (next, next_items, next_view);
+  }
+
+  events.sort_by_key(|event| event.date);
+  Ok(events)
+}
+
+/// Grandeurs surveillées pour `count` objets : longitude et élongation de chacun, séparation
+/// de chaque paire
+fn searches(count: usize) -> impl Iterator<Item = Search> {
+  (0..count)
+    .flat_map(|i| [Search::Longitude(i), Search::Elongation(i)])
+    .chain((0..count).flat_map(move |i| (i + 1..count).map(move |j| Search::Pair(i, j))))
+}
+
+/// Soleil et autres objets relativement à l'observateur
+fn view(items: &[CelestItem], observer: &str) -> Result<View> {
+  let observer = items
+    .iter()
+    .find(|item| vsop87::canonical_name(&item.name) == vsop87::canonical_name(observer))
+    .with_context(|| format!("observateur inconnu : {observer}"))?;
+  if vsop87::is_sun(&observer.name) {
+    bail!("l'observateur ne peut être le Soleil");
+  }
+  let sun = items.iter().find(|item| vsop87::is_sun(&item.name)).context("Soleil absent de la simulation")?;
+
+  let relative = |item: &CelestItem| (difference(item.position, observer.position), difference(item.velocity, observer.velocity));
+  let others: Vec<&CelestItem> = items.iter().filter(|item| item.name != observer.name && item.name != sun.name).collect();
+  Ok(View {
+    names: others.iter().map(|item| item.name.clone()).collect(),
+    sun: relative(sun),
+    bodies: others.into_iter().map(relative).collect(),
+  })
+}
+
+/// Dérivée temporelle du cosinus de l'angle entre les directions de deux objets
+fn direction_rate((p, v): Relative, (q, w): Relative) -> f64 {
+  let (u, e) = (unit(p), unit(q));
+  let du = difference(v, scale(u, dot(u, v)));
+  let de = difference(w, scale(e, dot(e, w)));
+  dot(du, e) / norm(p) + dot(u, de) / norm(q)
+}
+
+/// Sinus de l'écart de longitude écliptique de `b` par rapport à `a`
+fn longitude_sine(a: [f64; 3], b: [f64; 3]) -> f64 {
+  (a[0] * b[1] - a[1] * b[0]) / (a[0].hypot(a[1]) * b[0].hypot(b[1]))
+}
+
+fn longitude_cosine(a: [f64; 3], b: [f64; 3]) -> f64 {
+  (a[0] * b[0] + a[1] * b[1]) / (a[0].hypot(a[1]) * b[0].hypot(b[1]))
+}
+
+/// Angle entre deux directions, en degrés
+fn angle(a: [f64; 3], b: [f64; 3]) -> f64 {
+  let cross = [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]];
+  norm(cross).atan2(dot(a, b)).to_degrees()
+}
+
+fn difference(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+  std::array::from_fn(|k| a[k] - b[k])
+}
+
+fn scale(a: [f64; 3], factor: f64) -> [f64; 3] {
+  a.map(|x| x * factor)
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+  a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+  dot(a, a).sqrt()
+}
+
+fn unit(a: [f64; 3]) -> [f64; 3] {
+  scale(a, 1.0 / norm(a))
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::f64::consts::PI;
+  use std::sync::Arc;
+
+  use super::*;
+  use crate::bll::time;
+  use crate::bo::time_scale::TimeScale;
+  use crate::dal::dao_factory::DAOFactory;
+
+  const ASTRONOMICAL_UNIT: f64 = 1.495_978_707e11;
+  const MU: f64 = 1.327_124_4e20;
+  const DAY: f64 = 86_400.0;
+
+  /// Objet en orbite circulaire héliocentrique dans l'écliptique, de phase `phase` à t = 0
+  fn circular(name: &str, radius: f64, phase: f64, t: f64) -> CelestItem {
+    let r = radius * ASTRONOMICAL_UNIT;
+    let n = (MU / (r * r * r)).sqrt();
+    let (sin, cos) = (phase + n * t).sin_cos();
+    CelestItem {
+      name: name.into(),
+      position: [r * cos, r * sin, 0.0],
+      velocity: [-r * n * sin, r * n * cos, 0.0],
+      ..Default::default()
+    }
+  }
+
+  fn motion(radius: f64) -> f64 {
+    (MU / (radius * ASTRONOMICAL_UNIT).powi(3)).sqrt()
+  }
+
+  #[test]
+  fn conjunctions_oppositions_and_elongations_are_dated() {
+    let system = |t: f64| {
+      vec![
+        CelestItem { name: "Soleil".into(), ..Default::default() },
+        circular("Terre", 1.0, 0.0, t),
+        circular("Venus", 0.72, -PI / 2.0, t),
+        circular("Mars", 1.5, PI / 6.0, t),
+      ]
+    };
+    let from = Epoch::from_nanoseconds(0);
+    let search = SkySearch { max_separation: 180.0, ..SkySearch::default() };
+    let events = scan(from, from.after(365.0 * DAY), &search, system(0.0), |_, _, end| Ok(system(end.seconds_since(from)))).unwrap();
+
+    // Orbites circulaires coplanaires : dates et élongations se déduisent des phases héliocentriques
+    let (venus, mars) = (motion(0.72) - motion(1.0), motion(1.0) - motion(1.5));
+    let elongation = 0.72_f64.acos();
+    let expected = [
+      (SkyEventKind::Opposition, "Mars", (PI / 6.0) / mars, 180.0),
+      (SkyEventKind::GreatestEasternElongation, "Venus", (PI / 2.0 - elongation) / venus, 0.72_f64.asin().to_degrees()),
+      (SkyEventKind::InferiorConjunction, "Venus", (PI / 2.0) / venus, 0.0),
+      (SkyEventKind::GreatestWesternElongation, "Venus", (PI / 2.0 + elongation) / venus, 0.72_f64.asin().to_degrees()),
+    ];
+    let sun_events: Vec<&SkyEvent> = events.iter().filter(|event| event.kind != SkyEventKind::Grouping).collect();
+    assert_eq!(sun_events.len(), expected.len(), "{events:?}");
+    for (event, (kind, body, t, separation)) in sun_events.iter().zip(expected) {
+      assert_eq!((event.kind, event.bodies[0].as_str()), (kind, body));
+      assert!((event.date.seconds_since(from) - t).abs() < 1.0, "{event:?} {t}");
+      assert!((event.separation - separation).abs() < 1e-4, "{event:?}");
+    }
+
+    // Les regroupements sont des minima de la séparation apparente vue depuis la Terre
+    let groupings: Vec<&SkyEvent> = events.iter().filter(|event| event.kind == SkyEventKind::Grouping).collect();
+    assert!(!groupings.is_empty());
+    for event in groupings {
+      let separation = |t: f64| {
+        let items = system(t);
+        let earth = items[1].position;
+        angle(difference(items[2].position, earth), difference(items[3].position, earth))
+      };
+      let t = event.date.seconds_since(from);
+      assert!((separation(t) - event.separation).abs() < 1e-6, "{event:?}");
+      assert!(separation(t - 60.0) > event.separation && separation(t + 60.0) > event.separation, "{event:?}");
+    }
+  }
+
+  #[tokio::test]
+  async fn simulated_events_do_not_depend_on_sampling() {
+    let simulator = Simulator::new(Arc::new(DAOFactory::without_mongo()), "data/celest_items.json").await;
+    let from = time::from_julian_day(time::J2000_JULIAN_DAY, TimeScale::Tt).unwrap();
+    let to = from.after(60.0 * DAY);
+    let options = SimulationOptions::default();
+
+    // Échantillonnage par jour ou par demi-heure, en deçà du pas par défaut : mêmes événements,
+    // datés à quelques secondes près par la réintégration autour de chacun
+    let daily = find_sky_events(&simulator, from, to, &SkySearch::default(), &options).await.unwrap();
+    let fine = SkySearch { sample_step: 1800.0, ..SkySearch::default() };
+    let half_hourly = find_sky_events(&simulator, from, to, &fine, &options).await.unwrap();
+    assert!(!daily.is_empty());
+    assert_eq!(daily.len(), half_hourly.len(), "{daily:?} {half_hourly:?}");
+    for (a, b) in daily.iter().zip(&half_hourly) {
+      assert_eq!((a.kind, &a.bodies), (b.kind, &b.bodies));
+      // Les extrema d'élongation, plats, se datent moins finement que les conjonctions
+      assert!(a.date.seconds_since(b.date).abs() < 30.0, "{a:?} {b:?}");
+      assert!((a.separation - b.separation).abs() < 1e-3, "{a:?} {b:?}");
+    }
+
+    // Échantillonnage trop fin, ou trop d'instantanés pour l'intervalle
+    assert!(fine.validate(from, to).is_ok());
+    assert!(SkySearch { sample_step: 1.0, ..SkySearch::default() }.validate(from, to).is_err());
+    assert!(fine.validate(from, from.after(1e5 * 1800.0 + DAY)).is_err());
+  }
+}
@@ -0,0 +1,180 @@
+use crate::bo::celest_item::CelestItem;
+use crate::bo::force_parameters::ForceParameters;
+
+/// Vecteurs 3D rangés en structure de tableaux
+///
+/// Les composantes sont stockées dans un unique tampon contigu
+/// `[x0..xn, y0..yn, z0..zn]`, ce qui permet aux boucles de calcul d'opérer
+/// sur des tranches de f64 consécutifs (vectorisables) sans allocation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Vectors {
+  data: Vec<f64>,
+  len: usize,
+}
+
+impl Vectors {
+  pub fn zeros(len: usize) -> Self {
+    Vectors { data: vec![0.0; 3 * len], len }
+  }
+
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Redimensionne le tampon si nécessaire (sans allocation si la taille est inchangée)
+  pub fn resize(&mut self, len: usize) {
+    if self.len != len {
+      *self = Vectors::zeros(len);
+    }
+  }
+
+  pub fn get(&self, i: usize) -> [f64; 3] {
+    [self.data[i], self.data[self.len + i], self.data[2 * self.len + i]]
+  }
+
+  pub fn set(&mut self, i: usize, value: [f64; 3]) {
+    self.data[i] = value[0];
+    self.data[self.len + i] = value[1];
+    self.data[2 * self.len + i] = value[2];
+  }
+
+  pub fn components(&self) -> (&[f64], &[f64], &[f64]) {
+    let (x, rest) = self.data.split_at(self.len);
+    let (y, z) = rest.split_at(self.len);
+    (x, y, z)
+  }
+
+  pub fn components_mut(&mut self) -> (&mut [f64], &mut [f64], &mut [f64]) {
+    let (x, rest) = self.data.split_at_mut(self.len);
+    let (y, z) = rest.split_at_mut(self.len);
+    (x, y, z)
+  }
+
+  /// Tampon complet (3N valeurs)
+  pub fn as_slice(&self) -> &[f64] {
+    &self.data
+  }
+
+  pub fn as_mut_slice(&mut self) -> &mut [f64] {
+    &mut self.data
+  }
+
+  pub fn copy_from(&mut self, other: &Vectors) {
+    self.resize(other.len);
+    self.data.copy_from_slice(&other.data);
+  }
+
+  pub fn fill(&mut self, value: f64) {
+    self.data.fill(value);
+  }
+
+  /// Retire le vecteur d'indice `i` (réalloue le tampon, réservé aux événements rares)
+  pub fn remove(&mut self, i: usize) {
+    let len = self.len;
+    let mut data = Vec::with_capacity(3 * (len - 1));
+    for component in self.data.chunks_exact(len) {
+      data.extend_from_slice(&component[..i]);
+      data.extend_from_slice(&component[i + 1..]);
+    }
+    *self = Vectors { data, len: len - 1 };
+  }
+
+  /// self += factor · other
+  pub fn add_scaled(&mut self, other: &Vectors, factor: f64) {
+    for (a, b) in self.data.iter_mut().zip(&other.data) {
+      *a += factor * b;
+    }
+  }
+}
+
+/// État dynamique du système, converti depuis et vers `CelestItem` aux seules frontières de la simulation
+#[derive(Debug, Clone, Default)]
+pub struct SystemState {
+  pub positions: Vectors,  // en mètres
+  pub velocities: Vectors, // en m/s
+  pub masses: Vec<f64>,    // en kilogrammes
+  pub radii: Vec<f64>,     // en mètres
+  pub force_parameters: Vec<ForceParameters>,
+  fixed: Vec<usize>,
+}
+
+impl SystemState {
+  pub fn from_items(items: &[CelestItem]) -> Self {
+    let n = items.len();
+    let mut state = SystemState {
+      positions: Vectors::zeros(n),
+      velocities: Vectors::zeros(n),
+      // Une particule test n'est source d'aucune force : sa masse est nulle pour l'intégration
+      masses: items.iter().map(|item| if item.test_particle { 0.0 } else { item.mass }).collect(),
+      radii: items.iter().map(|item| item.radius).collect(),
+      force_parameters: items.iter().map(|item| item.forces.unwrap_or_default()).collect(),
+      fixed: Vec::new(),
+    };
+    for (i, item) in items.iter().enumerate() {
+      state.positions.set(i, item.position);
+      if item.fixed {
+        // Un corps fixe n'a ni vitesse ni accélération dans l'intégration
+        state.fixed.push(i);
+      } else {
+        state.velocities.set(i, item.velocity);
+      }
+    }
+    state
+  }
+
+  /// Recopie l'état dans les objets célestes (position et vitesse des corps fixes laissées intactes)
+  pub fn write_to(&self, items: &mut [CelestItem]) {
+    for (i, item) in items.iter_mut().enumerate() {
+      if !item.test_particle {
+        item.mass = self.masses[i];
+      }
+      item.radius = self.radii[i];
+      if self.is_fixed_index(i) {
+        continue;
+      }
+      item.position = self.positions.get(i);
+      item.velocity = self.velocities.get(i);
+    }
+  }
+
+  pub fn is_fixed_index(&self, i: usize) -> bool {
+    self.fixed.contains(&i)
+  }
+
+  pub fn len(&self) -> usize {
+    self.masses.len()
+  }
+
+  /// Nombre de corps soumis à l'intégration
+  pub fn mobile_count(&self) -> usize {
+    self.len() - self.fixed.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.masses.is_empty()
+  }
+
+  /// Retire l'objet d'indice `i`, les suivants sont décalés d'un rang
+  pub fn remove(&mut self, i: usize) {
+    self.positions.remove(i);
+    self.velocities.remove(i);
+    self.masses.remove(i);
+    self.radii.remove(i);
+    self.force_parameters.remove(i);
+    self.fixed.retain(|&f| f != i);
+    for f in self.fixed.iter_mut().filter(|f| **f > i) {
+      *f -= 1;
+    }
+  }
+
+  /// Annule les composantes des corps fixes (accélérations, dérivées)
+  pub fn pin(&self, vectors: &mut Vectors) {
+    for &i in &self.fixed {
+      vectors.set(i, [0.0; 3]);
+    }
+  }
+}
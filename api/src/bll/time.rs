@@ -0,0 +1,258 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeDelta};
+
+use crate::bo::epoch::Epoch;
+use crate::bo::time_scale::TimeScale;
+
+pub use crate::bo::utils::J2000_JULIAN_DAY;
+
+/// Écart entre le jour julien et le jour julien modifié
+pub const MODIFIED_JULIAN_DAY_OFFSET: f64 = 2_400_000.5;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+const NANOSECONDS_PER_SECOND: i128 = 1_000_000_000;
+
+/// Écart maximal à l'an 2000 des dates acceptées, en années
+pub const MAX_YEARS: i64 = 1_000_000_000;
+
+/// Jours d'un cycle de 400 ans, au bout duquel le calendrier grégorien se répète
+const DAYS_PER_GREGORIAN_CYCLE: i64 = 146_097;
+
+/// TT − TAI, en secondes
+const TT_MINUS_TAI: f64 = 32.184;
+
+/// TAI − UTC (s) en vigueur à partir du 1er du mois indiqué, depuis l'abandon des secondes élastiques
+const LEAP_SECONDS: [(i32, u32, f64); 28] = [
+  (1972, 1, 10.0),
+  (1972, 7, 11.0),
+  (1973, 1, 12.0),
+  (1974, 1, 13.0),
+  (1975, 1, 14.0),
+  (1976, 1, 15.0),
+  (1977, 1, 16.0),
+  (1978, 1, 17.0),
+  (1979, 1, 18.0),
+  (1980, 1, 19.0),
+  (1981, 7, 20.0),
+  (1982, 7, 21.0),
+  (1983, 7, 22.0),
+  (1985, 7, 23.0),
+  (1988, 1, 24.0),
+  (1990, 1, 25.0),
+  (1991, 1, 26.0),
+  (1992, 7, 27.0),
+  (1993, 7, 28.0),
+  (1994, 7, 29.0),
+  (1996, 1, 30.0),
+  (1997, 7, 31.0),
+  (1999, 1, 32.0),
+  (2006, 1, 33.0),
+  (2009, 1, 34.0),
+  (2012, 7, 35.0),
+  (2015, 7, 36.0),
+  (2017, 1, 37.0),
+];
+
+/// Date calendaire de J2000, origine des lectures d'horloge de chaque échelle
+fn j2000_label() -> NaiveDateTime {
+  NaiveDate::from_ymd_opt(2000, 1, 1).and_then(|day| day.and_hms_opt(12, 0, 0)).expect("Date J2000 invalide")
+}
+
+/// Nanosecondes de la date calendaire `date` depuis celle de J2000, hors secondes intercalaires
+fn label_nanoseconds(date: NaiveDateTime) -> i128 {
+  let delta = date - j2000_label();
+  delta.num_seconds() as i128 * NANOSECONDS_PER_SECOND + delta.subsec_nanos() as i128
+}
+
+fn nanoseconds(seconds: f64) -> i128 {
+  (seconds * NANOSECONDS_PER_SECOND as f64).round() as i128
+}
+
+/// TAI − UTC en secondes, pour une lecture UTC de `label` nanosecondes depuis J2000
+///
+/// Avant 1972, l'écart de 10 s est prolongé : UTC suivait alors le temps universel par
+/// des secondes élastiques, et ΔT n'est pas modélisé. Après la dernière seconde
+/// intercalaire de la table, l'écart est supposé constant.
+fn tai_minus_utc(label: i128) -> f64 {
+  LEAP_SECONDS
+    .iter()
+    .rev()
+    .find(|(year, month, _)| {
+      NaiveDate::from_ymd_opt(*year, *month, 1)
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .is_some_and(|day| label >= label_nanoseconds(day))
+    })
+    .map_or(LEAP_SECONDS[0].2, |(_, _, offset)| *offset)
+}
+
+/// TDB − TT (s), termes principaux de la série de Fairhead et Bretagnon, `days` jours après J2000
+fn tdb_minus_tt(days: f64) -> f64 {
+  let g = (357.53 + 0.985_600_28 * days).to_radians();
+  0.001_657 * g.sin() + 0.000_014 * (2.0 * g).sin()
+}
+
+/// Avance de TDB sur l'horloge de l'échelle `scale` lorsqu'elle indique `label`, en secondes
+fn tdb_offset(label: i128, scale: TimeScale) -> f64 {
+  let tt_offset = match scale {
+    TimeScale::Utc => tai_minus_utc(label) + TT_MINUS_TAI,
+    TimeScale::Tai => TT_MINUS_TAI,
+    TimeScale::Tt => 0.0,
+    TimeScale::Tdb => return 0.0,
+  };
+  // Terme périodique évalué à la lecture TT de l'instant, commune à toutes les échelles
+  let days = (label as f64 / NANOSECONDS_PER_SECOND as f64 + tt_offset) / SECONDS_PER_DAY;
+  tt_offset + tdb_minus_tt(days)
+}
+
+/// Instant auquel l'horloge de l'échelle `scale` indique `label` nanosecondes depuis J2000
+fn from_label(label: i128, scale: TimeScale) -> Result<Epoch> {
+  label
+    .checked_add(nanoseconds(tdb_offset(label, scale)))
+    .map(Epoch::from_nanoseconds)
+    .context("date hors de l'intervalle représentable")
+}
+
+/// Lecture de l'horloge de l'échelle `scale` à l'instant `epoch`, en nanosecondes depuis J2000
+fn to_label(epoch: Epoch, scale: TimeScale) -> i128 {
+  // L'écart à TDB dépend de la lecture cherchée : quelques itérations atteignent le point fixe
+  let mut label = epoch.nanoseconds();
+  for _ in 0..4 {
+    label = epoch.nanoseconds() - nanoseconds(tdb_offset(label, scale));
+  }
+  label
+}
+
+/// Jour julien de l'instant `epoch` dans l'échelle `scale`
+pub fn julian_day(epoch: Epoch, scale: TimeScale) -> f64 {
+  J2000_JULIAN_DAY + to_label(epoch, scale) as f64 / NANOSECONDS_PER_SECOND as f64 / SECONDS_PER_DAY
+}
+
+/// Instant correspondant au jour julien `julian_day` de l'échelle `scale`, à moins de
+/// `MAX_YEARS` années juliennes de J2000
+pub fn from_julian_day(julian_day: f64, scale: TimeScale) -> Result<Epoch> {
+  let days = julian_day - J2000_JULIAN_DAY;
+  if !days.is_finite() || days.abs() > MAX_YEARS as f64 * 365.25 {
+    bail!("jour julien hors de ±{MAX_YEARS} ans autour de J2000");
+  }
+  from_label(nanoseconds(days * SECONDS_PER_DAY), scale)
+}
+
+/// Instant dont la date calendaire ISO 8601 dans l'échelle `scale` est `label`
+///
+/// L'année peut être signée et compter plus de quatre chiffres (`-50000-03-21T00:00:00Z`) ;
+/// elle est ramenée d'un nombre entier de cycles grégoriens dans l'intervalle de `chrono`
+/// pour lire le reste de la date. L'année doit rester à moins de `MAX_YEARS` ans de l'an 2000.
+pub fn parse(label: &str, scale: TimeScale) -> Result<Epoch> {
+  let sign = usize::from(label.starts_with(['+', '-']));
+  let year_end = label[sign..].find('-').map(|end| sign + end).context("date ISO 8601 attendue")?;
+  let year: i64 = label[..year_end].parse().context("année invalide")?;
+  let offset = year
+    .checked_sub(2000)
+    .filter(|offset| offset.abs() <= MAX_YEARS)
+    .with_context(|| format!("année hors de ±{MAX_YEARS} ans autour de l'an 2000"))?;
+  let cycles = offset.div_euclid(400);
+  let shifted = format!("{:04}{}", year - 400 * cycles, &label[year_end..]);
+  let date = DateTime::parse_from_rfc3339(&shifted)?.naive_utc();
+  from_label(label_nanoseconds(date) + cycles as i128 * cycle_nanoseconds(), scale)
+}
+
+/// Date UTC ISO 8601 de l'instant `epoch`, avec une année étendue signée hors de 0000 à 9999
+pub fn format(epoch: Epoch) -> String {
+  let (year, date) = calendar(epoch);
+  format!("{year}{}", date.format("-%m-%dT%H:%M:%S%.fZ"))
+}
+
+/// Jour UTC de l'instant `epoch`, au format `AAAA-MM-JJ`
+pub fn format_day(epoch: Epoch) -> String {
+  let (year, date) = calendar(epoch);
+  format!("{year}{}", date.format("-%m-%d"))
+}
+
+fn cycle_nanoseconds() -> i128 {
+  DAYS_PER_GREGORIAN_CYCLE as i128 * SECONDS_PER_DAY as i128 * NANOSECONDS_PER_SECOND
+}
+
+/// Année UTC de l'instant `epoch`, mise en forme, et sa date ramenée entre 2000 et 2400
+/// d'un nombre entier de cycles grégoriens
+fn calendar(epoch: Epoch) -> (String, NaiveDateTime) {
+  let label = to_label(epoch, TimeScale::Utc);
+  let (cycles, rest) = (label.div_euclid(cycle_nanoseconds()), label.rem_euclid(cycle_nanoseconds()));
+  let delta = TimeDelta::new((rest / NANOSECONDS_PER_SECOND) as i64, (rest % NANOSECONDS_PER_SECOND) as u32)
+    .expect("Durée inférieure à un cycle grégorien");
+  let date = j2000_label() + delta;
+  let year = date.year() as i128 + 400 * cycles;
+  let year = if (0..=9999).contains(&year) { format!("{year:04}") } else { format!("{year:+05}") };
+  (year, date)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn utc(date: &str) -> Epoch {
+    parse(date, TimeScale::Utc).unwrap()
+  }
+
+  #[test]
+  fn scales_leap_seconds_and_julian_days() {
+    // J2000 est défini en TT, 64,184 s après 11 h 58 min 55,816 s UTC
+    let j2000 = from_julian_day(J2000_JULIAN_DAY, TimeScale::Tt).unwrap();
+    assert_eq!(j2000.to_string(), "2000-01-01T11:58:55.816Z");
+    assert!((julian_day(j2000, TimeScale::Tdb) - J2000_JULIAN_DAY).abs() * SECONDS_PER_DAY < 2e-4);
+    assert_eq!(from_julian_day(51_544.5 + MODIFIED_JULIAN_DAY_OFFSET, TimeScale::Tt).unwrap(), j2000);
+    assert_eq!(parse("2000-01-01T12:00:00Z", TimeScale::Tt).unwrap(), j2000);
+
+    // La seconde intercalaire du 31 décembre 2016 allonge la dernière minute de l'année
+    let (before, after_leap) = (utc("2016-12-31T23:59:00Z"), utc("2017-01-01T00:01:00Z"));
+    assert_eq!(tai_minus_utc(to_label(before, TimeScale::Utc)), 36.0);
+    assert_eq!(tai_minus_utc(to_label(after_leap, TimeScale::Utc)), 37.0);
+    assert!((after_leap.seconds_since(before) - 121.0).abs() < 1e-6);
+    assert!(before.after(121.0).seconds_since(after_leap).abs() < 1e-6);
+
+    // TDB − TT reste sous 1,7 ms et le jour julien fait l'aller-retour
+    let date = utc("2024-07-04T06:30:15.250Z");
+    let tdb_tt = (julian_day(date, TimeScale::Tdb) - julian_day(date, TimeScale::Tt)) * SECONDS_PER_DAY;
+    assert!(tdb_tt.abs() < 1.7e-3 && tdb_tt != 0.0, "{tdb_tt}");
+    for scale in [TimeScale::Utc, TimeScale::Tai, TimeScale::Tt, TimeScale::Tdb] {
+      let back = from_julian_day(julian_day(date, scale), scale).unwrap();
+      assert!(back.seconds_since(date).abs() < 1e-4, "{scale:?} {back}");
+    }
+  }
+
+  #[test]
+  fn deep_time_dates_keep_their_calendar() {
+    // Dates ordinaires et années étendues font l'aller-retour à la nanoseconde
+    for label in [
+      "2024-07-04T06:30:15.250Z",
+      "1582-10-15T00:00:00Z",
+      "0000-02-29T23:59:59.999999999Z",
+      "-0044-03-15T12:00:00Z",
+      "+10000-01-01T00:00:00Z",
+      "+1000000-06-30T18:00:00Z",
+      "-5000000-01-01T00:00:00Z",
+    ] {
+      assert_eq!(utc(label).to_string(), label);
+    }
+    assert_eq!(utc("2024-07-04T08:30:15+02:00"), utc("2024-07-04T06:30:15Z"));
+    assert_eq!(format_day(utc("-5000000-12-31T23:00:00-02:00")), "-4999999-01-01");
+
+    // Un million d'années grégoriennes, plus les 5 secondes intercalaires ajoutées depuis 2000
+    let (start, end) = (utc("2000-01-01T12:00:00Z"), utc("+1002000-01-01T12:00:00Z"));
+    let expected = 2500.0 * DAYS_PER_GREGORIAN_CYCLE as f64 * SECONDS_PER_DAY + 5.0;
+    assert!((end.seconds_since(start) - expected).abs() < 0.01, "{}", end.seconds_since(start));
+    let jd = julian_day(end, TimeScale::Tt);
+    assert!(from_julian_day(jd, TimeScale::Tt).unwrap().seconds_since(end).abs() < 1.0);
+    assert!(parse("-123", TimeScale::Utc).is_err() && parse("2024-13-01T00:00:00Z", TimeScale::Utc).is_err());
+
+    // Dates hors de portée refusées plutôt que de déborder
+    for label in ["-9223372036854775808-01-01T00:00:00Z", "+1000002001-01-01T00:00:00Z"] {
+      assert!(parse(label, TimeScale::Utc).is_err(), "{label}");
+    }
+    assert!(utc("-999998000-01-01T00:00:00Z") < utc("-5000000-01-01T00:00:00Z"));
+    for jd in [f64::NAN, f64::INFINITY, 1e300, -1e300, 4e11] {
+      assert!(from_julian_day(jd, TimeScale::Tt).is_err(), "{jd}");
+    }
+    assert!(from_julian_day(-3e11, TimeScale::Tt).is_ok());
+  }
+}
@@ -1,12 +1,28 @@
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use crate::bo::epoch::Epoch;
+use crate::bo::force_parameters::ForceParameters;
+use crate::bo::orbital_elements::InitialOrbit;
+use crate::bo::spacecraft::Spacecraft;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct CelestItem {
     pub name: String,
     pub mass: f64,       // en kilogrammes
     pub radius: f64,     // en mètres
+    #[serde(default)]
     pub position: [f64; 3], // en mètres
+    #[serde(default)]
     pub velocity: [f64; 3], // en m/s
-    pub timestamp: Option<DateTime<Utc>>, // date de la simulation
+    pub timestamp: Option<Epoch>, // date de la simulation
+    #[serde(default)]
+    pub fixed: bool, // objet maintenu immobile par l'intégration
+    #[serde(default)]
+    pub test_particle: bool, // soumis à la gravitation des corps massifs sans l'exercer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forces: Option<ForceParameters>, // forces non gravitationnelles (pression de radiation, Yarkovsky, poussée)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spacecraft: Option<Spacecraft>, // engin spatial manœuvrable, ergols restants compris
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orbit: Option<InitialOrbit>, // conditions initiales en éléments moyens, remplacées au chargement par position et vitesse
 }
\ No newline at end of file
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bo::epoch::Epoch;
+use crate::bo::simulation_options::CollisionMode;
+
+/// Contact entre deux objets détecté pendant l'intégration
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CollisionEvent {
+    pub date: Epoch,
+    pub bodies: [String; 2],   // le premier subsiste en cas de fusion
+    pub outcome: CollisionMode,
+    pub relative_speed: f64,   // en m/s
+    pub position: [f64; 3],    // point de contact, en mètres
+}
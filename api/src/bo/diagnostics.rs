@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bo::epoch::Epoch;
+
+/// Grandeurs conservées du système à un instant donné
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct ConservedQuantities {
+    pub energy: f64,                // en joules
+    pub linear_momentum: [f64; 3],  // en kg·m/s
+    pub angular_momentum: [f64; 3], // en kg·m²/s
+    pub center_of_mass: [f64; 3],   // en mètres
+}
+
+/// Bilan de conservation entre le début et la fin d'une simulation
+///
+/// Les erreurs sont relatives : l'énergie et le moment cinétique par rapport à leur
+/// valeur initiale, la quantité de mouvement par rapport à Σ m|v| et la dérive du
+/// centre de masse (écart au mouvement rectiligne uniforme) par rapport à la taille du système.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Diagnostics {
+    pub start: ConservedQuantities,
+    pub end: ConservedQuantities,
+    pub energy_error: f64,
+    pub linear_momentum_error: f64,
+    pub angular_momentum_error: f64,
+    pub center_of_mass_error: f64,
+    pub timestamp: Option<Epoch>, // date de la simulation
+}
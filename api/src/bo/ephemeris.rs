@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bo::epoch::Epoch;
+
+/// Source des positions renvoyées par l'API
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EphemerisKind {
+    /// Intégration numérique depuis les conditions initiales
+    #[default]
+    Numerical,
+    /// Théorie analytique VSOP87 du Soleil et des huit planètes
+    Vsop87,
+}
+
+/// Écart entre les positions numérique et analytique d'un corps
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PositionDifference {
+    pub name: String,
+    pub timestamp: Option<Epoch>,
+    pub numerical: [f64; 3],     // position héliocentrique intégrée, en mètres
+    pub analytic: [f64; 3],      // position héliocentrique analytique, en mètres
+    pub difference: [f64; 3],    // numérique − analytique, en mètres
+    pub distance: f64,           // norme de l'écart, en mètres
+    pub angular_separation: f64, // écart vu du Soleil, en secondes d'arc
+}
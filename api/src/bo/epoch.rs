@@ -0,0 +1,77 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::bll::time;
+use crate::bo::time_scale::TimeScale;
+
+const NANOSECONDS_PER_SECOND: f64 = 1e9;
+
+/// Instant de la simulation, en nanosecondes de temps dynamique barycentrique (TDB) depuis J2000
+///
+/// Contrairement à `chrono`, limité à ±262 000 ans, l'entier sur 128 bits couvre des
+/// milliards d'années à la nanoseconde près. Un instant s'écrit en date UTC ISO 8601,
+/// avec une année étendue signée (`+1000000-01-01T00:00:00Z`) hors des années 0000 à 9999.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch {
+    nanoseconds: i128,
+}
+
+impl Epoch {
+    /// Instant situé `nanoseconds` nanosecondes TDB après J2000
+    pub const fn from_nanoseconds(nanoseconds: i128) -> Self {
+        Epoch { nanoseconds }
+    }
+
+    /// Nanosecondes TDB écoulées depuis J2000
+    pub const fn nanoseconds(self) -> i128 {
+        self.nanoseconds
+    }
+
+    /// Secondes TDB écoulées depuis J2000
+    pub fn seconds(self) -> f64 {
+        self.nanoseconds as f64 / NANOSECONDS_PER_SECOND
+    }
+
+    /// Temps dynamique écoulé depuis `origin`, en secondes, calculé sans perte sur l'écart
+    pub fn seconds_since(self, origin: Epoch) -> f64 {
+        (self.nanoseconds - origin.nanoseconds) as f64 / NANOSECONDS_PER_SECOND
+    }
+
+    /// Instant atteint `seconds` secondes de temps dynamique plus tard
+    ///
+    /// Panique hors de l'intervalle représentable : les durées fournies par les requêtes
+    /// passent par `checked_after`.
+    pub fn after(self, seconds: f64) -> Self {
+        self.checked_after(seconds).expect("Instant hors de l'intervalle représentable")
+    }
+
+    /// Instant atteint `seconds` secondes plus tard, `None` si la durée n'est pas finie ou
+    /// si l'instant sort de l'intervalle représentable
+    pub fn checked_after(self, seconds: f64) -> Option<Self> {
+        let delta = (seconds * NANOSECONDS_PER_SECOND).round();
+        if !delta.is_finite() || delta.abs() >= i128::MAX as f64 {
+            return None;
+        }
+        self.nanoseconds.checked_add(delta as i128).map(Epoch::from_nanoseconds)
+    }
+}
+
+impl fmt::Display for Epoch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&time::format(*self))
+    }
+}
+
+impl Serialize for Epoch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Epoch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let label = String::deserialize(deserializer)?;
+        time::parse(&label, TimeScale::Utc).map_err(serde::de::Error::custom)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// Direction d'une poussée, définie relativement au corps dominant ou dans les axes de la simulation
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrustDirection {
+    /// Selon la vitesse relative
+    #[default]
+    Prograde,
+    /// À l'opposé de la vitesse relative
+    Retrograde,
+    /// En s'éloignant du corps dominant
+    Radial,
+    /// Vers le corps dominant
+    AntiRadial,
+    /// Selon le moment cinétique orbital r × v
+    Normal,
+    /// À l'opposé du moment cinétique orbital
+    AntiNormal,
+    /// Direction fixe dans les axes de la simulation
+    Inertial([f64; 3]),
+}
+
+/// Poussée continue d'accélération constante
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Thrust {
+    pub acceleration: f64, // en m/s²
+    #[serde(default)]
+    pub direction: ThrustDirection,
+}
+
+/// Paramètres des forces non gravitationnelles subies par un objet
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct ForceParameters {
+    pub area_to_mass: f64, // surface exposée rapportée à la masse, en m²/kg
+    pub reflectivity: f64, // coefficient de pression de radiation C_r (1 : absorbant, 2 : miroir)
+    pub yarkovsky: f64,    // accélération transverse A2 à 1 UA, en m/s² (négative pour une rotation rétrograde)
+    pub thrust: Option<Thrust>,
+}
+
+impl Default for ForceParameters {
+    fn default() -> Self {
+        ForceParameters { area_to_mass: 0.0, reflectivity: 1.0, yarkovsky: 0.0, thrust: None }
+    }
+}
@@ -1 +1,15 @@
-pub mod celest_item;
\ No newline at end of file
+pub mod celest_item;
+pub mod collision_event;
+pub mod diagnostics;
+pub mod ephemeris;
+pub mod epoch;
+pub mod force_parameters;
+pub mod orbital_elements;
+pub mod orbital_event;
+pub mod reference_frame;
+pub mod simulation_options;
+pub mod simulation_result;
+pub mod sky_event;
+pub mod spacecraft;
+pub mod time_scale;
+pub mod utils;
\ No newline at end of file
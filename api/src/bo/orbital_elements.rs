@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+
+use std::f64::consts::{PI, TAU};
+
+use crate::bo::epoch::Epoch;
+
+// En deçà de cet écart à 1, l'orbite est traitée comme parabolique
+const PARABOLIC_TOLERANCE: f64 = 1e-9;
+// En deçà, l'excentricité ou l'inclinaison est considérée nulle et l'angle associé indéfini
+const DEGENERATE_TOLERANCE: f64 = 1e-11;
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Angle orienté de `from` à `to` autour de l'axe unitaire `axis`
+fn angle(from: [f64; 3], to: [f64; 3], axis: [f64; 3]) -> f64 {
+    dot(axis, cross(from, to)).atan2(dot(from, to))
+}
+
+/// Éléments képlériens osculateurs relatifs à un corps central
+///
+/// Les angles sont en degrés, dans les axes du repère des positions. L'anomalie moyenne est
+/// M = E − e sin E pour une ellipse, M = e sinh H − H pour une hyperbole et, pour une
+/// parabole, M = D + D³/3 avec D = tan(ν/2) (équation de Barker). La distance au
+/// périapside décrit tous les cas ; le demi-grand axe est négatif pour une hyperbole
+/// et infini (`null` en JSON) pour une parabole.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f64,             // a, en mètres
+    pub eccentricity: f64,                // e
+    pub inclination: f64,                 // i, dans [0, 180]
+    pub longitude_of_ascending_node: f64, // Ω, nul pour une orbite équatoriale
+    pub argument_of_periapsis: f64,       // ω, nul pour une orbite circulaire
+    pub mean_anomaly: f64,                // M, comptée depuis le nœud pour une orbite circulaire
+    pub periapsis_distance: f64,          // q, en mètres
+}
+
+impl OrbitalElements {
+    /// Éléments de l'état relatif (`position` en m, `velocity` en m/s) pour le paramètre
+    /// gravitationnel `mu` = G (M + m), en m³/s²
+    pub fn from_state(position: [f64; 3], velocity: [f64; 3], mu: f64) -> Self {
+        let (r, v) = (position, velocity);
+        let distance = norm(r);
+        let h = cross(r, v);
+        let h_norm = norm(h);
+        let axis = h.map(|x| x / h_norm);
+        let node = [-h[1], h[0], 0.0];
+        let node_norm = norm(node);
+
+        let radial_factor = dot(v, v) - mu / distance;
+        let r_dot_v = dot(r, v);
+        let e_vector: [f64; 3] = std::array::from_fn(|k| (radial_factor * r[k] - r_dot_v * v[k]) / mu);
+        let e = norm(e_vector);
+        let p = h_norm * h_norm / mu;
+        let q = p / (1.0 + e);
+
+        let inclination = (h[2] / h_norm).clamp(-1.0, 1.0).acos();
+        let inclined = node_norm > DEGENERATE_TOLERANCE * h_norm;
+        let eccentric = e > DEGENERATE_TOLERANCE;
+        // Directions de référence : nœud ascendant, à défaut l'axe x
+        let node_direction = if inclined { node } else { [1.0, 0.0, 0.0] };
+        let node_longitude = if inclined { node[1].atan2(node[0]) } else { 0.0 };
+        let periapsis_direction = if eccentric { e_vector } else { node_direction };
+        let argument_of_periapsis = if eccentric {
+            if inclined {
+                angle(node, e_vector, axis)
+            } else {
+                // Longitude du périapside, comptée dans le sens du mouvement
+                e_vector[1].atan2(e_vector[0]) * axis[2].signum()
+            }
+        } else {
+            0.0
+        };
+        let true_anomaly = angle(periapsis_direction, r, axis);
+
+        let (semi_major_axis, mean_anomaly) = if (e - 1.0).abs() < PARABOLIC_TOLERANCE {
+            let d = (0.5 * true_anomaly).tan();
+            (f64::INFINITY, d + d * d * d / 3.0)
+        } else if e < 1.0 {
+            let eccentric_anomaly = ((1.0 - e * e).sqrt() * true_anomaly.sin()).atan2(e + true_anomaly.cos());
+            (q / (1.0 - e), (eccentric_anomaly - e * eccentric_anomaly.sin()).rem_euclid(TAU))
+        } else {
+            let hyperbolic_anomaly = 2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * (0.5 * true_anomaly).tan()).atanh();
+            (q / (1.0 - e), e * hyperbolic_anomaly.sinh() - hyperbolic_anomaly)
+        };
+
+        OrbitalElements {
+            semi_major_axis,
+            eccentricity: e,
+            inclination: inclination.to_degrees(),
+            longitude_of_ascending_node: node_longitude.rem_euclid(TAU).to_degrees(),
+            argument_of_periapsis: argument_of_periapsis.rem_euclid(TAU).to_degrees(),
+            mean_anomaly: mean_anomaly.to_degrees(),
+            periapsis_distance: q,
+        }
+    }
+
+    /// État relatif (position en m, vitesse en m/s) correspondant aux éléments, pour le
+    /// paramètre gravitationnel `mu` = G (M + m)
+    pub fn to_state(self, mu: f64) -> ([f64; 3], [f64; 3]) {
+        let e = self.eccentricity;
+        let mean_anomaly = self.mean_anomaly.to_radians();
+
+        let true_anomaly = if (e - 1.0).abs() < PARABOLIC_TOLERANCE {
+            // Solution algébrique de l'équation de Barker
+            let w = 1.5 * mean_anomaly;
+            let s = (w + (w * w + 1.0).sqrt()).cbrt();
+            2.0 * (s - 1.0 / s).atan()
+        } else if e < 1.0 {
+            let m = (mean_anomaly + PI).rem_euclid(TAU) - PI;
+            let mut eccentric_anomaly = if e < 0.8 { m } else { PI.copysign(m) };
+            for _ in 0..50 {
+                let delta = (eccentric_anomaly - e * eccentric_anomaly.sin() - m) / (1.0 - e * eccentric_anomaly.cos());
+                eccentric_anomaly -= delta;
+                if delta.abs() < 1e-15 {
+                    break;
+                }
+            }
+            2.0 * ((1.0 + e).sqrt() * (0.5 * eccentric_anomaly).sin()).atan2((1.0 - e).sqrt() * (0.5 * eccentric_anomaly).cos())
+        } else {
+            let m = mean_anomaly;
+            let mut hyperbolic_anomaly = (m / e).asinh();
+            for _ in 0..50 {
+                let delta = (e * hyperbolic_anomaly.sinh() - hyperbolic_anomaly - m) / (e * hyperbolic_anomaly.cosh() - 1.0);
+                hyperbolic_anomaly -= delta;
+                if delta.abs() < 1e-15 * hyperbolic_anomaly.abs().max(1.0) {
+                    break;
+                }
+            }
+            2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (0.5 * hyperbolic_anomaly).tanh()).atan()
+        };
+
+        // État dans le plan de l'orbite, le périapside selon le premier axe
+        let p = self.periapsis_distance * (1.0 + e);
+        let distance = p / (1.0 + e * true_anomaly.cos());
+        let speed = (mu / p).sqrt();
+        let (sin_nu, cos_nu) = true_anomaly.sin_cos();
+        let position = [distance * cos_nu, distance * sin_nu];
+        let velocity = [-speed * sin_nu, speed * (e + cos_nu)];
+
+        // Rotations R3(Ω) R1(i) R3(ω) vers les axes de la simulation
+        let (sin_o, cos_o) = self.longitude_of_ascending_node.to_radians().sin_cos();
+        let (sin_i, cos_i) = self.inclination.to_radians().sin_cos();
+        let (sin_w, cos_w) = self.argument_of_periapsis.to_radians().sin_cos();
+        let p_axis = [cos_o * cos_w - sin_o * sin_w * cos_i, sin_o * cos_w + cos_o * sin_w * cos_i, sin_w * sin_i];
+        let q_axis = [-cos_o * sin_w - sin_o * cos_w * cos_i, -sin_o * sin_w + cos_o * cos_w * cos_i, cos_w * sin_i];
+        let rotate = |plane: [f64; 2]| -> [f64; 3] { std::array::from_fn(|k| plane[0] * p_axis[k] + plane[1] * q_axis[k]) };
+        (rotate(position), rotate(velocity))
+    }
+}
+
+/// Éléments moyens d'une orbite au format des tables d'éphémérides (Standish, JPL)
+///
+/// Les longitudes sont comptées depuis l'équinoxe : ϖ = Ω + ω et L = ϖ + M.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct MeanElements {
+    pub semi_major_axis: f64,             // a, en mètres
+    pub eccentricity: f64,                // e
+    pub inclination: f64,                 // i, en degrés
+    pub mean_longitude: f64,              // L, en degrés
+    pub longitude_of_perihelion: f64,     // ϖ, en degrés
+    pub longitude_of_ascending_node: f64, // Ω, en degrés
+}
+
+impl MeanElements {
+    /// Éléments osculateurs équivalents, les éléments moyens étant pris pour osculateurs à leur époque
+    pub fn osculating(self) -> OrbitalElements {
+        OrbitalElements {
+            semi_major_axis: self.semi_major_axis,
+            eccentricity: self.eccentricity,
+            inclination: self.inclination,
+            longitude_of_ascending_node: self.longitude_of_ascending_node.rem_euclid(360.0),
+            argument_of_periapsis: (self.longitude_of_perihelion - self.longitude_of_ascending_node).rem_euclid(360.0),
+            mean_anomaly: (self.mean_longitude - self.longitude_of_perihelion).rem_euclid(360.0),
+            periapsis_distance: self.semi_major_axis * (1.0 - self.eccentricity),
+        }
+    }
+}
+
+/// Conditions initiales données par des éléments moyens relatifs à un corps central
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct InitialOrbit {
+    pub central_body: String,
+    #[serde(flatten)]
+    pub elements: MeanElements,
+}
+
+/// Éléments osculateurs d'un objet à une date, relativement au corps central indiqué
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BodyElements {
+    pub name: String,
+    pub timestamp: Option<Epoch>,
+    pub central_body: String,
+    #[serde(flatten)]
+    pub elements: OrbitalElements,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Paramètre gravitationnel du Soleil (m³/s²) et unité astronomique (m)
+    const MU: f64 = 1.327_124_400_18e20;
+    const AU: f64 = 1.495_978_707e11;
+
+    fn assert_close(a: [f64; 3], b: [f64; 3], tolerance: f64) {
+        let scale = norm(b).max(1.0);
+        let error = norm(std::array::from_fn(|k| a[k] - b[k]));
+        assert!(error <= tolerance * scale, "{a:?} ≠ {b:?}");
+    }
+
+    #[test]
+    fn state_round_trip_covers_all_conics() {
+        let circular = (MU / AU).sqrt();
+        let cases = [
+            // Ellipse inclinée quelconque
+            ([AU, 0.3 * AU, 0.1 * AU], [-5e3, 2.6e4, 3e3]),
+            // Orbites circulaires, inclinée puis équatoriales prograde et rétrograde
+            ([AU, 0.0, 0.0], [0.0, circular * 0.8, circular * 0.6]),
+            ([AU, 0.0, 0.0], [0.0, circular, 0.0]),
+            ([AU, 0.0, 0.0], [0.0, -circular, 0.0]),
+            // Ellipses équatoriales prograde et rétrograde
+            ([0.0, AU, 0.0], [-3.4e4, 5e3, 0.0]),
+            ([0.0, AU, 0.0], [3.4e4, 5e3, 0.0]),
+            // Parabole au périhélie puis en branche sortante, hyperbole
+            ([AU, 0.0, 0.0], [0.0, (2.0 * MU / AU).sqrt(), 0.0]),
+            ([0.0, 2.0 * AU, 0.0], [-(MU / AU).sqrt(), 0.0, 0.0]),
+            ([AU, -AU, 0.2 * AU], [1e4, 4.5e4, -2e3]),
+        ];
+        for (position, velocity) in cases {
+            let elements = OrbitalElements::from_state(position, velocity, MU);
+            let (r, v) = elements.to_state(MU);
+            assert_close(r, position, 1e-9);
+            assert_close(v, velocity, 1e-9);
+        }
+    }
+
+    #[test]
+    fn elements_round_trip() {
+        let elements = OrbitalElements {
+            semi_major_axis: 2.77 * AU,
+            eccentricity: 0.0785,
+            inclination: 10.59,
+            longitude_of_ascending_node: 80.3,
+            argument_of_periapsis: 73.6,
+            mean_anomaly: 291.4,
+            periapsis_distance: 2.77 * AU * (1.0 - 0.0785),
+        };
+        let (r, v) = elements.to_state(MU);
+        let back = OrbitalElements::from_state(r, v, MU);
+        assert!((back.semi_major_axis / elements.semi_major_axis - 1.0).abs() < 1e-12);
+        assert!((back.eccentricity - elements.eccentricity).abs() < 1e-12);
+        for (a, b) in [
+            (back.inclination, elements.inclination),
+            (back.longitude_of_ascending_node, elements.longitude_of_ascending_node),
+            (back.argument_of_periapsis, elements.argument_of_periapsis),
+            (back.mean_anomaly, elements.mean_anomaly),
+        ] {
+            assert!((a - b).abs() < 1e-9, "{a} ≠ {b}");
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bo::epoch::Epoch;
+
+/// Nature d'un événement orbital
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrbitalEventKind {
+    /// Passage au plus près du corps de référence
+    Periapsis,
+    /// Passage au plus loin du corps de référence
+    Apoapsis,
+    /// Traversée du plan de référence vers le nord
+    AscendingNode,
+    /// Traversée du plan de référence vers le sud
+    DescendingNode,
+    /// Minimum de distance entre deux objets sous le seuil demandé
+    CloseApproach,
+}
+
+/// Événement orbital daté par recherche de racine pendant l'intégration
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OrbitalEvent {
+    pub date: Epoch,
+    pub kind: OrbitalEventKind,
+    pub bodies: [String; 2],   // objet puis corps de référence, ou les deux objets d'un rapprochement
+    pub distance: f64,         // distance entre les deux corps, en mètres
+    pub relative_speed: f64,   // en m/s
+}
\ No newline at end of file
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// Origine du repère des positions et vitesses renvoyées
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameOrigin {
+    /// Barycentre des corps massifs, origine de la simulation
+    #[default]
+    Barycentric,
+    /// Centre du Soleil
+    Heliocentric,
+    /// Centre d'un objet quelconque désigné par son nom, `{"body": "Terre"}` en JSON
+    Body(String),
+}
+
+/// Plan fondamental des axes, l'axe x pointant vers l'équinoxe J2000
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferencePlane {
+    /// Écliptique J2000, plan de la simulation
+    #[default]
+    Ecliptic,
+    /// Équateur de l'ICRF
+    Equatorial,
+}
+
+/// Repère dans lequel exprimer les objets renvoyés par l'API
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ReferenceFrame {
+    pub origin: FrameOrigin,
+    pub plane: ReferencePlane,
+}
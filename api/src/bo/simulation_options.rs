@@ -0,0 +1,229 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Schéma d'intégration numérique utilisé par le simulateur
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegratorKind {
+    /// Euler semi-implicite historique, conservé pour comparaison
+    Euler,
+    /// Velocity Verlet (symplectique, ordre 2)
+    #[default]
+    VelocityVerlet,
+    /// Leapfrog kick-drift-kick (symplectique, ordre 2)
+    Leapfrog,
+    /// Runge–Kutta Dormand–Prince 5(4) à pas adaptatif piloté par `tolerance`
+    DormandPrince,
+    /// Gauss–Radau d'ordre 15 à pas adaptatif (IAS15), pour les rencontres proches
+    Ias15,
+    /// Wisdom–Holman en coordonnées de Jacobi, pour les intégrations longues à grand pas
+    WisdomHolman,
+    /// Propagation analytique à deux corps autour du corps dominant, en un seul pas quelle que soit la date
+    Kepler,
+}
+
+impl IntegratorKind {
+    /// Les schémas adaptatifs choisissent leur pas et ne sont pas bornés en nombre de pas
+    pub fn is_adaptive(self) -> bool {
+        matches!(self, IntegratorKind::DormandPrince | IntegratorKind::Ias15)
+    }
+
+    /// La propagation analytique couvre tout l'intervalle sans pas d'intégration
+    pub fn is_analytic(self) -> bool {
+        self == IntegratorKind::Kepler
+    }
+}
+
+/// Tolérances d'erreur locale des intégrateurs adaptatifs
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct Tolerance {
+    pub relative: f64,
+    pub absolute: f64, // en m pour les positions, en m/s pour les vitesses
+}
+
+impl Tolerance {
+    /// En deçà, l'erreur d'arrondi des `f64` empêche le contrôle d'erreur d'accepter un pas
+    pub const MIN_RELATIVE: f64 = 1e-14;
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance { relative: 1e-10, absolute: 1e-3 }
+    }
+}
+
+/// Méthode de calcul des forces gravitationnelles
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForceSolverKind {
+    /// Sommation directe en O(N²)
+    #[default]
+    Direct,
+    /// Octree de Barnes–Hut en O(N log N), précision réglée par `opening_angle`
+    BarnesHut,
+}
+
+/// Accélération caractéristique de MOND (m/s²)
+fn default_acceleration_scale() -> f64 {
+    1.2e-10
+}
+
+/// Loi d'interaction gravitationnelle entre deux masses
+///
+/// Le bilan d'énergie des diagnostics reste calculé avec le potentiel newtonien.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ForceLawKind {
+    /// Newton, interaction ignorée à moins d'un kilomètre
+    #[default]
+    Newtonian,
+    /// Newton adouci de Plummer, `softening` étant la longueur ε (m)
+    Softened { softening: f64 },
+    /// Newton avec un terme de Yukawa d'intensité α (`strength`) et de portée λ (`range`, m)
+    Yukawa {
+        range: f64,
+        strength: f64,
+        #[serde(default)]
+        softening: f64,
+    },
+    /// MOND avec la fonction d'interpolation « simple » et l'échelle a₀ (m/s²)
+    Mond {
+        #[serde(default = "default_acceleration_scale")]
+        acceleration_scale: f64,
+        #[serde(default)]
+        softening: f64,
+    },
+}
+
+/// Traitement des objets dont les rayons se recouvrent en fin de pas
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionMode {
+    /// Les objets se traversent, comme avant la détection des collisions
+    #[default]
+    Ignore,
+    /// Fusion parfaitement inélastique conservant masse, quantité de mouvement et volume
+    Merge,
+    /// Rebond élastique le long de la ligne des centres
+    Bounce,
+    /// Arrêt de la simulation en erreur
+    Halt,
+}
+
+/// Événements orbitaux recherchés pendant l'intégration
+///
+/// Apsides et nœuds sont rapportés au corps de référence `central_body`, le plus massif
+/// par défaut, et au plan xy du repère de la simulation (l'écliptique J2000).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct EventOptions {
+    /// Passages au périapside et à l'apoapside
+    pub apsides: bool,
+    /// Passages aux nœuds ascendant et descendant
+    pub nodes: bool,
+    /// Distance (m) en deçà de laquelle le minimum de distance entre deux objets est signalé
+    pub close_approach: Option<f64>,
+    pub central_body: Option<String>,
+    /// Pas maximal (s) : deux événements de même nature dans un pas ne sont pas distingués
+    pub max_step: f64,
+}
+
+impl Default for EventOptions {
+    fn default() -> Self {
+        EventOptions { apsides: true, nodes: true, close_approach: None, central_body: None, max_step: 86_400.0 }
+    }
+}
+
+/// Paramètres de simulation choisis par requête
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SimulationOptions {
+    pub integrator: IntegratorKind,
+    pub tolerance: Tolerance,
+    /// Pas imposé aux intégrateurs à pas fixe (s), d'au moins `MIN_TIME_STEP`
+    pub time_step: Option<f64>,
+    pub force_solver: ForceSolverKind,
+    /// Angle d'ouverture θ de Barnes–Hut (0 redonne la sommation directe)
+    pub opening_angle: f64,
+    pub force_law: ForceLawKind,
+    /// Correction relativiste post-newtonienne (1PN) due au corps dominant
+    pub post_newtonian: bool,
+    pub collisions: CollisionMode,
+    /// Détection des événements orbitaux, désactivée par défaut
+    pub events: Option<EventOptions>,
+}
+
+impl Default for SimulationOptions {
+    fn default() -> Self {
+        SimulationOptions {
+            integrator: IntegratorKind::default(),
+            tolerance: Tolerance::default(),
+            time_step: None,
+            force_solver: ForceSolverKind::default(),
+            opening_angle: 0.5,
+            force_law: ForceLawKind::default(),
+            post_newtonian: false,
+            collisions: CollisionMode::default(),
+            events: None,
+        }
+    }
+}
+
+impl SimulationOptions {
+    /// Pas imposé minimal (s)
+    pub const MIN_TIME_STEP: f64 = 1.0;
+
+    /// Vérifie la cohérence des paramètres fournis par la requête
+    pub fn validate(&self) -> Result<()> {
+        if let Some(time_step) = self.time_step
+            && !(time_step.is_finite() && time_step >= Self::MIN_TIME_STEP)
+        {
+            bail!("time_step doit valoir au moins {} s", Self::MIN_TIME_STEP);
+        }
+        if !(self.tolerance.relative.is_finite() && self.tolerance.relative >= Tolerance::MIN_RELATIVE) {
+            bail!("tolerance.relative doit valoir au moins {:e}", Tolerance::MIN_RELATIVE);
+        }
+        // Une tolérance absolue nulle rend indéterminée l'erreur des composantes nulles
+        if !(self.tolerance.absolute.is_finite() && self.tolerance.absolute > 0.0) {
+            bail!("tolerance.absolute doit être strictement positive");
+        }
+        if !(self.opening_angle.is_finite() && self.opening_angle >= 0.0) {
+            bail!("opening_angle doit être positif");
+        }
+        let (softening, parameters_valid) = match self.force_law {
+            ForceLawKind::Newtonian => (0.0, true),
+            ForceLawKind::Softened { softening } => (softening, true),
+            ForceLawKind::Yukawa { range, strength, softening } => {
+                (softening, range.is_finite() && range > 0.0 && strength.is_finite())
+            }
+            ForceLawKind::Mond { acceleration_scale, softening } => {
+                (softening, acceleration_scale.is_finite() && acceleration_scale > 0.0)
+            }
+        };
+        if !(softening.is_finite() && softening >= 0.0) {
+            bail!("softening doit être positif");
+        }
+        if !parameters_valid {
+            bail!("paramètres de force_law invalides");
+        }
+        // Les dérives képlériennes supposent une interaction newtonienne
+        if self.integrator == IntegratorKind::WisdomHolman && self.force_law != ForceLawKind::Newtonian {
+            bail!("wisdom_holman n'accepte que la loi newtonienne");
+        }
+        if self.integrator.is_analytic() && (self.force_law != ForceLawKind::Newtonian || self.post_newtonian) {
+            bail!("kepler n'accepte que la loi newtonienne, sans correction post-newtonienne");
+        }
+        if let Some(events) = &self.events {
+            if !(events.max_step.is_finite() && events.max_step > 0.0) {
+                bail!("max_step doit être strictement positif");
+            }
+            if let Some(distance) = events.close_approach
+                && !(distance.is_finite() && distance > 0.0)
+            {
+                bail!("close_approach doit être strictement positif");
+            }
+        }
+        Ok(())
+    }
+}
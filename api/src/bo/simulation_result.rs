@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bo::celest_item::CelestItem;
+use crate::bo::collision_event::CollisionEvent;
+use crate::bo::diagnostics::Diagnostics;
+use crate::bo::orbital_elements::BodyElements;
+use crate::bo::orbital_event::OrbitalEvent;
+use crate::bo::simulation_options::IntegratorKind;
+
+/// Statistiques de l'intégration numérique
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrationStats {
+    pub integrator: IntegratorKind,
+    pub accepted_steps: u64,
+    pub rejected_steps: u64,
+}
+
+/// Résultat d'une simulation renvoyé par l'API
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SimulationResult {
+    pub celest_items: Vec<CelestItem>,
+    #[serde(default)]
+    pub test_particles: Vec<CelestItem>, // particules test, séparées des corps massifs
+    pub stats: Option<IntegrationStats>, // absent pour un résultat lu depuis le cache
+    pub diagnostics: Option<Diagnostics>,
+    #[serde(default)]
+    pub collisions: Vec<CollisionEvent>,
+    #[serde(default)]
+    pub events: Vec<OrbitalEvent>, // événements orbitaux, dans l'ordre chronologique
+    #[serde(default)]
+    pub orbital_elements: Vec<BodyElements>, // éléments osculateurs, calculés à la demande de l'API
+}
@@ -0,0 +1,74 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::bo::epoch::Epoch;
+
+/// Nature d'un événement céleste vu depuis le corps observateur
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkyEventKind {
+    /// Même longitude écliptique que le Soleil, l'objet étant plus proche que lui
+    InferiorConjunction,
+    /// Même longitude écliptique que le Soleil, l'objet étant au-delà
+    SuperiorConjunction,
+    /// Longitude écliptique opposée à celle du Soleil
+    Opposition,
+    /// Écart maximal au Soleil d'un objet intérieur, à l'est (visible le soir)
+    GreatestEasternElongation,
+    /// Écart maximal au Soleil d'un objet intérieur, à l'ouest (visible le matin)
+    GreatestWesternElongation,
+    /// Minimum de séparation entre deux objets sous le seuil demandé
+    Grouping,
+}
+
+/// Événement céleste daté par recherche de racine entre les instantanés de la simulation
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SkyEvent {
+    pub date: Epoch,
+    pub kind: SkyEventKind,
+    pub bodies: Vec<String>,   // objet observé, ou les deux objets d'un regroupement
+    pub separation: f64,       // séparation angulaire au Soleil, ou entre les deux objets, en degrés
+}
+
+/// Paramètres de la recherche d'événements célestes
+///
+/// Les positions sont géométriques : ni le temps de trajet de la lumière ni l'aberration
+/// ne sont pris en compte. Les longitudes sont comptées dans l'écliptique J2000.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SkySearch {
+    /// Corps d'où le ciel est observé
+    pub observer: String,
+    /// Séparation (°) en deçà de laquelle le rapprochement de deux objets est signalé
+    pub max_separation: f64,
+    /// Intervalle (s) entre deux instantanés, d'au moins `MIN_SAMPLE_STEP` : deux événements
+    /// de même nature dans un intervalle ne sont pas distingués
+    pub sample_step: f64,
+}
+
+impl Default for SkySearch {
+    fn default() -> Self {
+        SkySearch { observer: "Terre".into(), max_separation: 5.0, sample_step: 86_400.0 }
+    }
+}
+
+impl SkySearch {
+    /// Intervalle minimal entre deux instantanés (s)
+    pub const MIN_SAMPLE_STEP: f64 = 60.0;
+    /// Nombre d'instantanés au-delà duquel une recherche est refusée plutôt que de bloquer le serveur
+    pub const MAX_SAMPLES: f64 = 100_000.0;
+
+    /// Vérifie la cohérence des paramètres fournis par la requête pour l'intervalle [from, to]
+    pub fn validate(&self, from: Epoch, to: Epoch) -> Result<()> {
+        if !(self.max_separation.is_finite() && self.max_separation >= 0.0) {
+            bail!("max_separation doit être positive");
+        }
+        if !(self.sample_step.is_finite() && self.sample_step >= Self::MIN_SAMPLE_STEP) {
+            bail!("sample_step doit valoir au moins {} s", Self::MIN_SAMPLE_STEP);
+        }
+        if to.seconds_since(from).abs() / self.sample_step > Self::MAX_SAMPLES {
+            bail!("intervalle trop long pour {} instantanés : augmenter sample_step", Self::MAX_SAMPLES);
+        }
+        Ok(())
+    }
+}
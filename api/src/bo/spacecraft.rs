@@ -0,0 +1,104 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::bo::epoch::Epoch;
+use crate::bo::force_parameters::{ForceParameters, ThrustDirection};
+
+/// Repère dans lequel est exprimé un incrément de vitesse
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ManeuverFrame {
+    /// Axes de la simulation
+    #[default]
+    Inertial,
+    /// Vitesse, normale et binormale de l'orbite autour du corps dominant
+    Vnb,
+}
+
+/// Manœuvre programmée d'un engin spatial
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Maneuver {
+    /// Incrément de vitesse instantané
+    Impulsive {
+        epoch: Epoch,
+        delta_v: [f64; 3], // en m/s
+        #[serde(default)]
+        frame: ManeuverFrame,
+        isp: f64, // impulsion spécifique, en secondes
+    },
+    /// Poussée continue consommant des ergols
+    Finite {
+        start: Epoch,
+        duration: f64, // en secondes
+        thrust: f64,   // en newtons
+        isp: f64,      // impulsion spécifique, en secondes
+        #[serde(default)]
+        direction: ThrustDirection,
+    },
+}
+
+/// Impulsion exécutée, telle qu'obtenue avec les ergols disponibles
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExecutedImpulse {
+    pub epoch: Epoch,
+    pub delta_v: [f64; 3], // incrément obtenu dans les axes de la simulation, en m/s
+    pub propellant: f64,   // ergols brûlés, en kilogrammes
+}
+
+/// Engin spatial : ergols embarqués et programme de manœuvres
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Spacecraft {
+    pub propellant: f64, // masse d'ergols restante, en kilogrammes, comprise dans la masse de l'objet
+    #[serde(default)]
+    pub maneuvers: Vec<Maneuver>,
+    /// Impulsions déjà exécutées, qu'une intégration à rebours annule exactement
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executed: Vec<ExecutedImpulse>,
+}
+
+impl Spacecraft {
+    /// Vérifie la cohérence des ergols avec la masse totale `mass`, des paramètres des manœuvres
+    /// et des forces `forces` subies par l'engin
+    pub fn validate(&self, mass: f64, forces: Option<&ForceParameters>) -> Result<()> {
+        if !(self.propellant.is_finite() && self.propellant >= 0.0 && self.propellant < mass) {
+            bail!("propellant doit être positif et inférieur à la masse de l'engin");
+        }
+        for maneuver in &self.maneuvers {
+            let valid = match *maneuver {
+                Maneuver::Impulsive { delta_v, isp, .. } => delta_v.iter().all(|x| x.is_finite()) && isp > 0.0,
+                Maneuver::Finite { start, duration, thrust, isp, .. } => {
+                    start.checked_after(duration).is_some() && duration >= 0.0 && thrust >= 0.0 && isp > 0.0
+                }
+            };
+            if !valid {
+                bail!("paramètres de manœuvre invalides");
+            }
+        }
+
+        // Les poussées continues programmées occupent la force de poussée de l'engin
+        let has_burns = self.maneuvers.iter().any(|maneuver| matches!(maneuver, Maneuver::Finite { .. }));
+        if has_burns && forces.is_some_and(|forces| forces.thrust.is_some()) {
+            bail!("forces.thrust ne peut s'ajouter aux poussées continues programmées");
+        }
+
+        // Un engin ne porte qu'une poussée continue à la fois
+        let mut burns: Vec<(Epoch, f64)> = self
+            .maneuvers
+            .iter()
+            .filter_map(|maneuver| match *maneuver {
+                Maneuver::Finite { start, duration, .. } => Some((start, duration)),
+                Maneuver::Impulsive { .. } => None,
+            })
+            .collect();
+        burns.sort_by_key(|(start, _)| *start);
+        for pair in burns.windows(2) {
+            let (start, duration) = pair[0];
+            let end = start.after(duration);
+            if end > pair[1].0 {
+                bail!("les poussées continues d'un même engin ne doivent pas se chevaucher");
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Échelle de temps dans laquelle une date est exprimée
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeScale {
+    /// Temps universel coordonné, avec secondes intercalaires
+    #[default]
+    Utc,
+    /// Temps atomique international
+    Tai,
+    /// Temps terrestre, TAI + 32,184 s
+    Tt,
+    /// Temps dynamique barycentrique, argument des éphémérides
+    Tdb,
+}
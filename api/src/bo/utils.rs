@@ -0,0 +1,30 @@
+use crate::bo::epoch::Epoch;
+
+/// Constante gravitationnelle (m³ kg⁻¹ s⁻²)
+pub const G: f64 = 6.67430e-11;
+
+/// Jour julien de l'époque J2000, 1er janvier 2000 à 12 h TT
+pub const J2000_JULIAN_DAY: f64 = 2_451_545.0;
+
+/// Obliquité de l'écliptique J2000 (IAU 2006), en degrés
+pub const OBLIQUITY_J2000: f64 = 84_381.406 / 3600.0;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Instant du jour julien `julian_day` compté en TDB, l'échelle des instants de la simulation,
+/// `None` hors de l'intervalle représentable
+pub fn from_tdb_julian_day(julian_day: f64) -> Option<Epoch> {
+    Epoch::from_nanoseconds(0).checked_after((julian_day - J2000_JULIAN_DAY) * SECONDS_PER_DAY)
+}
+
+/// Rotation de l'écliptique J2000 vers le plan équatorial ICRF
+pub fn ecliptic_to_equatorial([x, y, z]: [f64; 3]) -> [f64; 3] {
+    let (sin, cos) = OBLIQUITY_J2000.to_radians().sin_cos();
+    [x, cos * y - sin * z, sin * y + cos * z]
+}
+
+/// Rotation du plan équatorial ICRF vers l'écliptique J2000
+pub fn equatorial_to_ecliptic([x, y, z]: [f64; 3]) -> [f64; 3] {
+    let (sin, cos) = OBLIQUITY_J2000.to_radians().sin_cos();
+    [x, cos * y + sin * z, -sin * y + cos * z]
+}
@@ -2,14 +2,16 @@ use std::fs::{create_dir_all, File};
 use std::io::{BufReader, Write};
 use std::path::Path;
 use anyhow::{Result, Context};
-use chrono::{DateTime, Utc};
 use serde_json;
 
+use crate::bll::time;
 use crate::bo::celest_item::CelestItem;
+use crate::bo::diagnostics::Diagnostics;
+use crate::bo::epoch::Epoch;
 
 const CACHE_DIR: &str = "data/cache";
 
-/// JSON Files Persistence
+/// JSON Files Persistence, one directory per dataset
 pub struct CachePersistor;
 
 impl CachePersistor {
@@ -17,13 +19,21 @@ impl CachePersistor {
     CachePersistor
   }
 
-  fn cache_path(date: DateTime<Utc>) -> String {
-    format!("{}/{}.json", CACHE_DIR, date.format("%Y-%m-%d"))
+  fn dataset_dir(dataset: &str) -> String {
+    format!("{}/{}", CACHE_DIR, dataset)
   }
 
-  pub async fn save(&self, date: DateTime<Utc>, items: &[CelestItem]) -> Result<()> {
-    create_dir_all(CACHE_DIR).context("Impossible de créer le dossier cache")?;
-    let path = Self::cache_path(date);
+  fn cache_path(dataset: &str, date: Epoch) -> String {
+    format!("{}/{}.json", Self::dataset_dir(dataset), time::format_day(date))
+  }
+
+  fn diagnostics_path(dataset: &str, date: Epoch) -> String {
+    format!("{}/{}.diagnostics.json", Self::dataset_dir(dataset), time::format_day(date))
+  }
+
+  pub async fn save(&self, dataset: &str, date: Epoch, items: &[CelestItem]) -> Result<()> {
+    create_dir_all(Self::dataset_dir(dataset)).context("Impossible de créer le dossier cache")?;
+    let path = Self::cache_path(dataset, date);
     let json = serde_json::to_string_pretty(items)?;
     let mut file = File::create(&path).context("Erreur lors de la création du fichier de cache")?;
     file.write_all(json.as_bytes())?;
@@ -31,8 +41,9 @@ impl CachePersistor {
     Ok(())
   }
 
-  pub async fn find_by_date(&self, date: DateTime<Utc>) -> Result<Option<Vec<CelestItem>>> {
-    let path = Self::cache_path(date);
+  #[allow(dead_code)]
+  pub async fn find_by_date(&self, dataset: &str, date: Epoch) -> Result<Option<Vec<CelestItem>>> {
+    let path = Self::cache_path(dataset, date);
     if !Path::new(&path).exists() {
         return Ok(None);
     }
@@ -44,4 +55,14 @@ impl CachePersistor {
     println!("♻️ Données chargées depuis cache : {}", path);
     Ok(Some(items))
   }
+
+  pub async fn save_diagnostics(&self, dataset: &str, date: Epoch, diagnostics: &Diagnostics) -> Result<()> {
+    create_dir_all(Self::dataset_dir(dataset)).context("Impossible de créer le dossier cache")?;
+    let path = Self::diagnostics_path(dataset, date);
+    let json = serde_json::to_string_pretty(diagnostics)?;
+    let mut file = File::create(&path).context("Erreur lors de la création du fichier de diagnostics")?;
+    file.write_all(json.as_bytes())?;
+    println!("💾 Diagnostics sauvegardés : {}", path);
+    Ok(())
+  }
 }
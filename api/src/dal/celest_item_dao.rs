@@ -1,8 +1,9 @@
-use chrono::{DateTime, Utc};
 use anyhow::Result;
 use std::sync::Arc;
 
 use crate::bo::celest_item::CelestItem;
+use crate::bo::diagnostics::Diagnostics;
+use crate::bo::epoch::Epoch;
 use crate::dal::celest_item_repository::{CelestItemRepository, CelestItemRepositoryTrait, PersistenceTarget};
 
 pub struct CelestItemDAO {
@@ -12,8 +13,12 @@ pub struct CelestItemDAO {
 
 impl CelestItemDAO {
   pub async fn new(persistence_target: PersistenceTarget) -> Self {
-    let repository = Arc::new(CelestItemRepository::new().await);
-    Self { repository, persistence_target }
+    Self::with_repository(CelestItemRepository::new().await, persistence_target)
+  }
+
+  /// Build the DAO on an already configured repository
+  pub fn with_repository(repository: CelestItemRepository, persistence_target: PersistenceTarget) -> Self {
+    Self { repository: Arc::new(repository), persistence_target }
   }
 
   /// Load CelestItem list from a file
@@ -21,18 +26,33 @@ impl CelestItemDAO {
     self.repository.load_celest_items(file_path).await
   }
 
-  /// Load CelestItem list for a given simulation date
-  pub async fn find_by_date(&self, date: DateTime<Utc>) -> Result<Vec<CelestItem>> {
-    self.repository.find_by_date(date).await
+  /// Load CelestItem list of a dataset for a given simulation date
+  pub async fn find_by_date(&self, dataset: &str, date: Epoch) -> Result<Vec<CelestItem>> {
+    self.repository.find_by_date(dataset, date).await
+  }
+
+  /// Load CelestItem list of a dataset for a given date range from cache
+  pub async fn find_by_dates(&self, dataset: &str, start: Epoch, stop: Epoch) -> Result<Vec<CelestItem>> {
+    self.repository.find_by_dates(dataset, start, stop).await
+  }
+
+  /// Load the stored snapshot of a dataset closest to a given date, before or after it
+  pub async fn find_nearest_by_date(&self, dataset: &str, date: Epoch) -> Result<Vec<CelestItem>> {
+    self.repository.find_nearest_by_date(dataset, date).await
+  }
+
+  /// Save simulation results under a dataset
+  pub async fn save_many(&self, dataset: &str, items: &[CelestItem]) -> Result<()> {
+    self.repository.save_many(dataset, items, self.persistence_target).await
   }
 
-  /// Load CelestItem list for a given date range from cache
-  pub async fn find_by_dates(&self, start: DateTime<Utc>, stop: DateTime<Utc>) -> Result<Vec<CelestItem>> {
-    self.repository.find_by_dates(start, stop).await
+  /// Load conservation diagnostics of a dataset for a given simulation date
+  pub async fn find_diagnostics_by_date(&self, dataset: &str, date: Epoch) -> Result<Option<Diagnostics>> {
+    self.repository.find_diagnostics_by_date(dataset, date).await
   }
 
-  /// Save simulation results
-  pub async fn save_many(&self, items: &[CelestItem]) -> Result<()> {
-    self.repository.save_many(items, self.persistence_target).await
+  /// Save conservation diagnostics of a simulation under a dataset
+  pub async fn save_diagnostics(&self, dataset: &str, diagnostics: &Diagnostics) -> Result<()> {
+    self.repository.save_diagnostics(dataset, diagnostics, self.persistence_target).await
   }
 }
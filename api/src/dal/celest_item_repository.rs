@@ -1,16 +1,20 @@
 use std::env;
-use std::{fs::File, io::BufReader, path::Path};
-use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::Path;
 use anyhow::{Context, Result};
 use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::bo::celest_item::CelestItem;
+use crate::bo::diagnostics::Diagnostics;
+use crate::bo::epoch::Epoch;
 use crate::dal::cache::CachePersistor;
+use crate::dal::horizons;
 use crate::dal::mongo::MongoPersistor;
 
 /// Enum pour spécifier la cible de persistance
 #[derive(Debug, Clone, Copy)]
+#[allow(dead_code)]
 pub enum PersistenceTarget {
   Cache,
   Mongo,
@@ -18,18 +22,29 @@ pub enum PersistenceTarget {
 }
 
 /// Interface pour lire et écrire des objets célestes
+///
+/// Les résultats sont rangés par jeu de données, l'empreinte des conditions initiales dont
+/// ils sont issus.
 #[async_trait]
 pub trait CelestItemRepositoryTrait: Send + Sync {
-  async fn save(&self, item: &CelestItem, target: PersistenceTarget) -> Result<()>;
-  async fn save_many(&self, items: &[CelestItem], target: PersistenceTarget) -> Result<()>;
-  async fn find_by_date(&self, date: DateTime<Utc>) -> Result<Vec<CelestItem>>;
-  async fn find_by_dates(&self, start: DateTime<Utc>, stop: DateTime<Utc>) -> Result<Vec<CelestItem>>;
+  #[allow(dead_code)]
+  async fn save(&self, dataset: &str, item: &CelestItem, target: PersistenceTarget) -> Result<()>;
+  async fn save_many(&self, dataset: &str, items: &[CelestItem], target: PersistenceTarget) -> Result<()>;
+  async fn find_by_date(&self, dataset: &str, date: Epoch) -> Result<Vec<CelestItem>>;
+  async fn find_by_dates(&self, dataset: &str, start: Epoch, stop: Epoch) -> Result<Vec<CelestItem>>;
+  async fn find_nearest_by_date(&self, dataset: &str, date: Epoch) -> Result<Vec<CelestItem>>;
+  async fn save_diagnostics(&self, dataset: &str, diagnostics: &Diagnostics, target: PersistenceTarget) -> Result<()>;
+  async fn find_diagnostics_by_date(&self, dataset: &str, date: Epoch) -> Result<Option<Diagnostics>>;
   async fn load_celest_items(&self, file_path: &str) -> Result<Vec<CelestItem>> {
-    // Default implementation to load CelestItem from a file
-    let file = File::open(file_path)
+    // Default implementation to load CelestItem from a JSON file or a JPL Horizons vector table
+    let text = fs::read_to_string(file_path)
       .context(format!("Open CelestItem file from path {} as failed", file_path))?;
-    let reader = BufReader::new(file);
-    let items: Vec<CelestItem> = serde_json::from_reader(reader)
+    let is_text_export = Path::new(file_path).extension().is_some_and(|extension| extension.eq_ignore_ascii_case("txt"));
+    if is_text_export || horizons::is_vectors_export(&text) {
+      return horizons::parse_vectors(&text)
+        .context(format!("Horizons vectors parse from file {} as failed.", file_path));
+    }
+    let items: Vec<CelestItem> = serde_json::from_str(&text)
       .context(format!("JSON parse from CelestItem file from path {} as failed.", file_path))?;
     Ok(items)
   }
@@ -43,37 +58,38 @@ pub struct CelestItemRepository {
 
 impl CelestItemRepository {
   pub async fn new() -> Self {
-    let cache = Arc::new(CachePersistor::new());
-    let mut mongo = None;
-
     let mongo_uri = env::var("MONGO_URI").ok();
     let mongo_db = env::var("MONGO_DB_NAME").ok();
     let mongo_collection = env::var("MONGO_COLLECTION_NAME").ok();
 
-    mongo = if let (Some(uri), Some(db), Some(coll)) = (mongo_uri, mongo_db, mongo_collection) {
-      let mongo_persistor = MongoPersistor::new(&uri, &db, &coll).await;
-      Some(Arc::new(mongo_persistor))
+    let mongo = if let (Some(uri), Some(db), Some(coll)) = (mongo_uri, mongo_db, mongo_collection) {
+      Some(MongoPersistor::new(&uri, &db, &coll).await)
     } else {
       tracing::warn!("MongoDB config not found: skipping Mongo persistence.");
       println!("⚠️  Wrong MongoDB config: skipping connection.");
       None
     };
 
-    if !mongo.is_none() {
+    if mongo.is_some() {
       tracing::info!("MongoDB connection is successful.");
     }
 
-    CelestItemRepository { cache, mongo }
+    Self::with_mongo(mongo)
+  }
+
+  /// Repository persistant dans `mongo` s'il est fourni, sans MongoDB sinon
+  pub fn with_mongo(mongo: Option<MongoPersistor>) -> Self {
+    CelestItemRepository { cache: Arc::new(CachePersistor::new()), mongo: mongo.map(Arc::new) }
   }
 }
 
 #[async_trait]
 impl CelestItemRepositoryTrait for CelestItemRepository {
-  async fn save(&self, item: &CelestItem, target: PersistenceTarget) -> Result<()> {
-    self.save_many(std::slice::from_ref(item), target).await
+  async fn save(&self, dataset: &str, item: &CelestItem, target: PersistenceTarget) -> Result<()> {
+    self.save_many(dataset, std::slice::from_ref(item), target).await
   }
 
-  async fn save_many(&self, items: &[CelestItem], target: PersistenceTarget) -> Result<()> {
+  async fn save_many(&self, dataset: &str, items: &[CelestItem], target: PersistenceTarget) -> Result<()> {
       if items.is_empty() {
         return Ok(());
       }
@@ -82,7 +98,7 @@ impl CelestItemRepositoryTrait for CelestItemRepository {
       match target {
         PersistenceTarget::Cache | PersistenceTarget::All => {
           if let Some(date) = items[0].timestamp {
-            self.cache.save(date, items).await?;
+            self.cache.save(dataset, date, items).await?;
           } else {
             tracing::warn!("Tried to save to cache with missing timestamp");
           }
@@ -91,20 +107,20 @@ impl CelestItemRepositoryTrait for CelestItemRepository {
       }
 
       // Persistence with MongoDB
-      if matches!(target, PersistenceTarget::Mongo | PersistenceTarget::All) {
-        if let Some(mongo) = &self.mongo {
-          mongo.save_many(items).await?;
-        }
+      if matches!(target, PersistenceTarget::Mongo | PersistenceTarget::All)
+        && let Some(mongo) = &self.mongo
+      {
+        mongo.save_many(dataset, items).await?;
       }
 
       Ok(())
   }
 
-  async fn find_by_date(&self, date: DateTime<Utc>) -> Result<Vec<CelestItem>> {
+  async fn find_by_date(&self, dataset: &str, date: Epoch) -> Result<Vec<CelestItem>> {
     // Find in MongoDB
     tracing::info!("Searching in MongoDB for date: {}", date);
     if let Some(mongo) = &self.mongo {
-      let results = mongo.find_by_date(date).await?;
+      let results = mongo.find_by_date(dataset, date).await?;
       if !results.is_empty() {
         return Ok(results);
       }
@@ -114,19 +130,56 @@ impl CelestItemRepositoryTrait for CelestItemRepository {
     // Find in cache files
     // let results = self.cache.find_by_date(date).await?;
     // Ok(results.unwrap_or_default())
-    return Ok(vec![]);
+    Ok(vec![])
   }
 
-  async fn find_by_dates(&self, start: DateTime<Utc>, stop: DateTime<Utc>) -> Result<Vec<CelestItem>> {
+  async fn find_by_dates(&self, dataset: &str, start: Epoch, stop: Epoch) -> Result<Vec<CelestItem>> {
     // Find in MongoDB
     tracing::info!("Searching in MongoDB for dates: {} to {}", start, stop);
     if let Some(mongo) = &self.mongo {
-      let results = mongo.find_by_dates(start, stop).await?;
+      let results = mongo.find_by_dates(dataset, start, stop).await?;
       if !results.is_empty() {
         return Ok(results);
       }
     }
     tracing::info!("No results found in MongoDB for dates: {} to {}", start, stop);
-    return Ok(vec![]);
+    Ok(vec![])
+  }
+
+  async fn find_nearest_by_date(&self, dataset: &str, date: Epoch) -> Result<Vec<CelestItem>> {
+    // Find in MongoDB
+    tracing::info!("Searching in MongoDB for nearest snapshot to date: {}", date);
+    if let Some(mongo) = &self.mongo {
+      return mongo.find_nearest_by_date(dataset, date).await;
+    }
+    Ok(vec![])
+  }
+
+  async fn save_diagnostics(&self, dataset: &str, diagnostics: &Diagnostics, target: PersistenceTarget) -> Result<()> {
+    // Persistence with files
+    if matches!(target, PersistenceTarget::Cache | PersistenceTarget::All) {
+      if let Some(date) = diagnostics.timestamp {
+        self.cache.save_diagnostics(dataset, date, diagnostics).await?;
+      } else {
+        tracing::warn!("Tried to save diagnostics to cache with missing timestamp");
+      }
+    }
+
+    // Persistence with MongoDB
+    if matches!(target, PersistenceTarget::Mongo | PersistenceTarget::All)
+      && let Some(mongo) = &self.mongo
+    {
+      mongo.save_diagnostics(dataset, diagnostics).await?;
+    }
+
+    Ok(())
+  }
+
+  async fn find_diagnostics_by_date(&self, dataset: &str, date: Epoch) -> Result<Option<Diagnostics>> {
+    // Find in MongoDB
+    if let Some(mongo) = &self.mongo {
+      return mongo.find_diagnostics_by_date(dataset, date).await;
+    }
+    Ok(None)
   }
 }
\ No newline at end of file
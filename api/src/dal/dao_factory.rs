@@ -15,6 +15,14 @@ impl DAOFactory {
       Self { celest_item_dao }
     }
 
+    /// Creates a DAOFactory that never reaches MongoDB, whatever the environment
+    #[cfg(test)]
+    pub fn without_mongo() -> Self {
+      let repository = crate::dal::celest_item_repository::CelestItemRepository::with_mongo(None);
+      let celest_item_dao = Arc::new(CelestItemDAO::with_repository(repository, PersistenceTarget::Mongo));
+      Self { celest_item_dao }
+    }
+
     pub fn celest_item_dao(&self) -> Arc<CelestItemDAO> {
       Arc::clone(&self.celest_item_dao)
     }
@@ -0,0 +1,332 @@
+use anyhow::{bail, Context, Result};
+
+use crate::bo::celest_item::CelestItem;
+use crate::bo::utils::{equatorial_to_ecliptic, from_tdb_julian_day, G};
+
+/// Unité astronomique, en mètres
+const ASTRONOMICAL_UNIT: f64 = 1.495_978_707e11;
+
+/// Indique si le texte est un export « VECTORS » de JPL Horizons
+pub fn is_vectors_export(text: &str) -> bool {
+  text.contains("$$SOE") && text.contains("Target body name:")
+}
+
+/// Objets célestes décrits par un export « VECTORS » de JPL Horizons
+///
+/// Le fichier peut concaténer les exports de plusieurs corps, au format tableau ou CSV.
+/// Chaque corps prend l'état de sa première ligne, converti en mètres et m/s dans le plan
+/// de l'écliptique J2000, et l'époque correspondante. Un corps dont le centre est un autre
+/// corps du fichier lui est rapporté ; un corps sans masse connue devient une particule test.
+pub fn parse_vectors(text: &str) -> Result<Vec<CelestItem>> {
+  // Chaque export se termine par `$$EOE` ; ses données physiques précèdent le nom du corps cible
+  let mut sections = vec![];
+  let mut section = vec![];
+  for line in text.lines() {
+    section.push(line);
+    if line.trim() == "$$EOE" {
+      sections.push(std::mem::take(&mut section));
+    }
+  }
+  if sections.is_empty() {
+    bail!("aucun bloc de vecteurs $$SOE … $$EOE dans l'export Horizons");
+  }
+
+  let mut bodies = sections.iter().map(|section| Body::parse(section)).collect::<Result<Vec<_>>>()?;
+
+  // Rattachement des corps rapportés à un autre corps de l'export, centres d'abord
+  loop {
+    let mut moved = false;
+    for index in 0..bodies.len() {
+      let Some(center) = bodies.iter().position(|body| body.item.name == bodies[index].center) else { continue };
+      if center == index || bodies[center].center_in(&bodies) {
+        continue;
+      }
+      let (origin, drift) = (bodies[center].item.position, bodies[center].item.velocity);
+      let root = bodies[center].center.clone();
+      let body = &mut bodies[index];
+      body.item.position = std::array::from_fn(|k| body.item.position[k] + origin[k]);
+      body.item.velocity = std::array::from_fn(|k| body.item.velocity[k] + drift[k]);
+      body.center = root;
+      moved = true;
+    }
+    if !moved {
+      break;
+    }
+  }
+
+  let root = &bodies[0].center;
+  if let Some(body) = bodies.iter().find(|body| body.center != *root && body.center_in(&bodies)) {
+    bail!("dépendance circulaire entre les centres de l'export Horizons : {}", body.item.name);
+  }
+  if let Some(body) = bodies.iter().find(|body| body.center != *root) {
+    bail!("centres incompatibles dans l'export Horizons : {} ({}) et {} ({})", bodies[0].item.name, root, body.item.name, body.center);
+  }
+  Ok(bodies.into_iter().map(|body| body.item).collect())
+}
+
+/// Corps lu dans l'export, avec le nom du centre auquel son état est rapporté
+struct Body {
+  item: CelestItem,
+  center: String,
+}
+
+impl Body {
+  fn center_in(&self, bodies: &[Body]) -> bool {
+    bodies.iter().any(|body| body.item.name == self.center)
+  }
+
+  fn parse(lines: &[&str]) -> Result<Body> {
+    let header_end = lines.iter().position(|line| line.trim() == "$$SOE").context("marqueur $$SOE absent")?;
+    let header = &lines[..header_end];
+    let name = body_name(header, "Target body name:").context("nom du corps cible absent")?;
+    let center = body_name(header, "Center body name:").with_context(|| format!("centre absent pour {name}"))?;
+
+    let (length, time) = match header_field(header, "Output units").as_deref() {
+      None | Some("KM-S") => (1e3, 1.0),
+      Some("KM-D") => (1e3, 86_400.0),
+      Some("AU-D") => (ASTRONOMICAL_UNIT, 86_400.0),
+      Some(units) => bail!("unités {units} non prises en charge pour {name}"),
+    };
+    let equatorial = match header_field(header, "Reference plane").as_deref() {
+      None | Some("ECLIPTIC") => false,
+      Some(plane) if plane.starts_with("FRAME") => true,
+      Some(plane) => bail!("plan de référence {plane} non pris en charge pour {name}"),
+    };
+
+    let data: Vec<&str> = lines[header_end + 1..]
+      .iter()
+      .take_while(|line| line.trim() != "$$EOE")
+      .copied()
+      .collect();
+    let record = if data.iter().any(|line| line.contains(',')) {
+      csv_record(header, &data)
+    } else {
+      table_record(&data)
+    }
+    .with_context(|| format!("vecteurs illisibles pour {name}"))?;
+
+    let mut position = [record.x, record.y, record.z].map(|x| x * length);
+    let mut velocity = [record.vx, record.vy, record.vz].map(|x| x * length / time);
+    if equatorial {
+      position = equatorial_to_ecliptic(position);
+      velocity = equatorial_to_ecliptic(velocity);
+    }
+
+    // GM en km³/s², rayon en km
+    let gm = physical_value(header, "GM", &["sigma"]).filter(|gm| *gm > 0.0);
+    let radius = physical_value(header, "mean radius", &[])
+      .or_else(|| physical_value(header, "radius", &["equ", "polar", "sigma"]))
+      .or_else(|| physical_value(header, "RAD", &[]))
+      .unwrap_or(0.0);
+    // Les dates des vecteurs Horizons sont des jours juliens TDB
+    let epoch = from_tdb_julian_day(record.julian_day).with_context(|| format!("date julienne hors de portée pour {name}"))?;
+
+    Ok(Body {
+      item: CelestItem {
+        name,
+        mass: gm.map_or(0.0, |gm| gm * 1e9 / G),
+        radius: radius * 1e3,
+        position,
+        velocity,
+        timestamp: Some(epoch),
+        fixed: false,
+        test_particle: gm.is_none(),
+        forces: None,
+        spacecraft: None,
+        orbit: None,
+      },
+      center,
+    })
+  }
+}
+
+/// Première ligne de vecteurs de l'export, dans les unités du fichier
+struct Record {
+  julian_day: f64,
+  x: f64,
+  y: f64,
+  z: f64,
+  vx: f64,
+  vy: f64,
+  vz: f64,
+}
+
+/// Nom d'un corps, sans son identifiant Horizons : « Mars (499) » donne « Mars »
+fn body_name(header: &[&str], label: &str) -> Option<String> {
+  let line = header.iter().find_map(|line| line.trim_start().strip_prefix(label))?;
+  let line = line.split('{').next()?.trim();
+  let name = match line.rfind(" (") {
+    Some(index) if line.ends_with(')') => &line[..index],
+    _ => line,
+  };
+  Some(name.trim().to_string()).filter(|name| !name.is_empty())
+}
+
+/// Valeur d'un champ d'en-tête « Libellé : valeur »
+fn header_field(header: &[&str], label: &str) -> Option<String> {
+  let line = header.iter().find_map(|line| line.trim_start().strip_prefix(label))?;
+  let value = line.trim_start().strip_prefix(':')?.trim();
+  Some(value.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Valeur numérique d'une donnée physique « clé … = valeur », insensible à la casse
+///
+/// Les lignes d'en-tête portent jusqu'à deux données ; la clé doit commencer un mot et
+/// son libellé ne doit contenir aucun des mots `excluded`.
+fn physical_value(header: &[&str], key: &str, excluded: &[&str]) -> Option<f64> {
+  let key = key.to_lowercase();
+  header.iter().find_map(|line| {
+    let line = line.to_lowercase();
+    line.match_indices(&key).find_map(|(index, _)| {
+      if line[..index].chars().next_back().is_some_and(|c| c.is_alphanumeric()) {
+        return None;
+      }
+      let rest = &line[index..];
+      let equal = rest.find('=')?;
+      let label = &rest[..equal];
+      if label.len() > 32 || excluded.iter().any(|word| label.contains(word)) {
+        return None;
+      }
+      let value = rest[equal + 1..].split_whitespace().next()?;
+      value.split("+-").next()?.trim_end_matches(|c: char| !c.is_ascii_digit()).parse().ok()
+    })
+  })
+}
+
+/// Première ligne du format tableau : date julienne puis « X = … Y = … Z = … VX= … »
+fn table_record(data: &[&str]) -> Result<Record> {
+  let start = data.iter().position(|line| first_number(line).is_some()).context("aucune ligne de vecteurs")?;
+  let julian_day = first_number(data[start]).context("date julienne absente")?;
+
+  let mut values = std::collections::HashMap::new();
+  for line in data[start + 1..].iter().take_while(|line| first_number(line).is_none()) {
+    let line = line.replace('=', " = ");
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    for window in tokens.windows(3) {
+      if window[1] == "=" {
+        values.insert(window[0].to_string(), window[2].parse::<f64>().ok());
+      }
+    }
+  }
+  let value = |key: &str| values.get(key).copied().flatten().with_context(|| format!("composante {key} absente"));
+  Ok(Record { julian_day, x: value("X")?, y: value("Y")?, z: value("Z")?, vx: value("VX")?, vy: value("VY")?, vz: value("VZ")? })
+}
+
+/// Première ligne du format CSV, dont les colonnes sont nommées juste avant `$$SOE`
+fn csv_record(header: &[&str], data: &[&str]) -> Result<Record> {
+  let columns: Vec<String> = header
+    .iter()
+    .rev()
+    .find(|line| line.contains("JDTDB") && line.contains(','))
+    .context("en-tête des colonnes CSV absent")?
+    .split(',')
+    .map(|column| column.trim().to_string())
+    .collect();
+  let line = data.iter().find(|line| !line.trim().is_empty()).context("aucune ligne de vecteurs")?;
+  let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+  let value = |key: &str| -> Result<f64> {
+    let index = columns.iter().position(|column| column == key).with_context(|| format!("colonne {key} absente"))?;
+    fields.get(index).and_then(|field| field.parse().ok()).with_context(|| format!("composante {key} illisible"))
+  };
+  Ok(Record { julian_day: value("JDTDB")?, x: value("X")?, y: value("Y")?, z: value("Z")?, vx: value("VX")?, vy: value("VY")?, vz: value("VZ")? })
+}
+
+/// Nombre en tête de ligne, hors ligne indentée : la date julienne d'un enregistrement
+fn first_number(line: &str) -> Option<f64> {
+  if line.starts_with(char::is_whitespace) {
+    return None;
+  }
+  line.split_whitespace().next()?.trim_end_matches(',').parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Export abrégé au format Horizons : Soleil au format tableau, Terre rapportée au Soleil,
+  // Lune en CSV dans le plan équatorial, en UA et UA/j, rapportée à la Terre. Les vecteurs
+  // de la Lune sont construits pour le test : 0,0027 UA et 1 km/s, aux longitudes et latitudes
+  // écliptiques de 220° et 5° pour la position, de 310° et −2° pour la vitesse
+  const EXPORT: &str = "\
+*******************************************************************************
+ Revised: July 31, 2013                  Sun                                 10
+
+ PHYSICAL PROPERTIES (updated 2018-Aug-15):
+  GM, km^3/s^2          = 132712440041.93938  Mass, 10^24 kg        = ~1988500
+  Vol. mean radius, km  = 695700              Volume, 10^12 km^3    = 1412000
+*******************************************************************************
+Target body name: Sun (10)                        {source: DE441}
+Center body name: Solar System Barycenter (0)     {source: DE441}
+Output units    : KM-S
+Reference frame : ICRF
+Reference plane : ECLIPTIC
+*******************************************************************************
+$$SOE
+2451545.000000000 = A.D. 2000-Jan-01 12:00:00.0000 TDB
+ X =-1.067706805380953E+06 Y =-4.182752406685240E+05 Z = 3.086181804401258E+04
+ VX= 9.312571926520856E-03 VY=-1.282475570794218E-02 VZ=-1.633507186624524E-04
+ LT= 3.831306107014496E+00 RG= 1.148598083761826E+06 RR=-2.058946211113853E-04
+$$EOE
+*******************************************************************************
+ Revised: April 12, 2021                 Earth                              399
+
+ GEOPHYSICAL PROPERTIES (revised May 9, 2022):
+  Vol. Mean Radius (km)    = 6371.01+-0.02   Mass x10^24 (kg)= 5.97219+-0.0006
+  GM, km^3/s^2             = 398600.435436   GM 1-sigma, km^3/s^2  = 0.0014
+*******************************************************************************
+Target body name: Earth (399)                     {source: DE441}
+Center body name: Sun (10)                        {source: DE441}
+Output units    : KM-S
+Reference plane : ECLIPTIC
+*******************************************************************************
+$$SOE
+2451545.000000000 = A.D. 2000-Jan-01 12:00:00.0000 TDB
+ X =-2.649903375682292E+07 Y = 1.446972967792532E+08 Z =-6.112214304122632E+02
+ VX=-2.979426006719171E+01 VY=-5.018052326235948E+00 VZ= 1.871223682892781E-04
+$$EOE
+*******************************************************************************
+Target body name: Moon (301)
+Center body name: Earth (399)
+Output units    : AU-D
+Reference plane : FRAME
+*******************************************************************************
+            JDTDB,            Calendar Date (TDB),                      X,                      Y,                      Z,                     VX,                     VY,                     VZ,
+**************************************************************************************************************************************************************************************************
+$$SOE
+2451545.000000000, A.D. 2000-Jan-01 12:00:00.0000, -2.060449414391978E-03, -1.679860454484497E-03,  -4.718231280796801E-04,  3.710147588936980E-04, -3.976545764089663E-04, -1.943732414325683E-04,
+$$EOE
+";
+
+  #[test]
+  fn vectors_export_is_converted_to_si_on_the_ecliptic() {
+    assert!(is_vectors_export(EXPORT));
+    let items = parse_vectors(EXPORT).unwrap();
+    let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names, ["Sun", "Earth", "Moon"]);
+
+    let (sun, earth, moon) = (&items[0], &items[1], &items[2]);
+    // Jour julien TDB de l'export, soit 11 h 58 min 55,8 s UTC
+    let j2000 = from_tdb_julian_day(crate::bo::utils::J2000_JULIAN_DAY).unwrap();
+    assert_eq!(j2000.nanoseconds(), 0);
+    assert!(j2000.to_string().starts_with("2000-01-01T11:58:55."));
+    assert!(items.iter().all(|item| item.timestamp == Some(j2000)));
+    assert!((sun.mass / 1.9885e30 - 1.0).abs() < 1e-3 && sun.radius == 6.957e8);
+    assert!((earth.mass / 5.97219e24 - 1.0).abs() < 1e-3 && (earth.radius - 6.37101e6).abs() < 1.0);
+    assert!(moon.test_particle && moon.mass == 0.0);
+
+    // Terre rapportée au barycentre via le Soleil
+    assert!((earth.position[0] - (-2.649903375682292e10 - 1.067706805380953e9)).abs() < 1.0);
+    assert!((earth.velocity[1] - (-5.018052326235948e3 - 1.282475570794218e1)).abs() < 1e-6);
+
+    // Lune ramenée dans l'écliptique par la rotation du plan équatorial
+    let relative: [f64; 3] = std::array::from_fn(|k| moon.position[k] - earth.position[k]);
+    let drift: [f64; 3] = std::array::from_fn(|k| moon.velocity[k] - earth.velocity[k]);
+    let distance = relative.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let speed = drift.iter().map(|x| x * x).sum::<f64>().sqrt();
+    assert!((distance / ASTRONOMICAL_UNIT - 0.0027).abs() < 1e-15 && (speed - 1000.0).abs() < 1e-9);
+    let angles = |v: [f64; 3], norm: f64| (v[1].atan2(v[0]).to_degrees().rem_euclid(360.0), (v[2] / norm).asin().to_degrees());
+    let ((longitude, latitude), (heading, climb)) = (angles(relative, distance), angles(drift, speed));
+    assert!((longitude - 220.0).abs() < 1e-9 && (latitude - 5.0).abs() < 1e-9, "{longitude} {latitude}");
+    assert!((heading - 310.0).abs() < 1e-9 && (climb + 2.0).abs() < 1e-9, "{heading} {climb}");
+  }
+}
@@ -2,4 +2,5 @@ pub mod dao_factory;
 pub mod celest_item_dao;
 pub mod celest_item_repository;
 pub mod cache;
-pub mod mongo;
\ No newline at end of file
+pub mod mongo;
+pub mod horizons;
\ No newline at end of file
@@ -1,19 +1,23 @@
 use std::sync::Arc;
 use anyhow::{Result, Context};
-use chrono::{DateTime, Utc};
 use mongodb::{
-  bson::{doc, DateTime as BsonDateTime, Document}, options::{ ClientOptions, FindOptions, IndexOptions }, Client, Collection, Database, IndexModel
+  bson::{self, doc, Document}, options::{ ClientOptions, FindOptions, IndexOptions }, Client, Collection, Database, IndexModel
 };
 use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
 
 use crate::bo::celest_item::CelestItem;
+use crate::bo::diagnostics::Diagnostics;
+use crate::bo::epoch::Epoch;
 
+#[allow(dead_code)]
 pub struct MongoDBClient {
   client: Client,
   database: Database,
   indexed: bool,
 }
 
+#[allow(dead_code)]
 impl MongoDBClient {
   pub async fn new(uri: &str, db_name: &str) -> mongodb::error::Result<Self> {
     let mut client_options = ClientOptions::parse(uri).await?;
@@ -60,7 +64,7 @@ impl MongoDBClient {
       return true;
     }
 
-    // Check MongoDB Index { name: 1, timestamp: 1 } exists
+    // Check MongoDB Index { dataset: 1, name: 1, timestamp: 1 } exists
     // Get the collection as a BSON Document collection (for listing raw indexes)
     let collection = self.collection::<Document>(collection_name);
 
@@ -97,6 +101,12 @@ impl MongoDBClient {
       // Access the keys field of the index. It is a BSON Document.
       let keys: &Document = &index.keys;
 
+      // Try to extract the "dataset" key as an integer
+      let dataset_ok = match keys.get("dataset") {
+        Some(bson_value) => bson_value.as_i32() == Some(1),
+        None => false,
+      };
+
       // Try to extract the "name" key as an integer
       let name_ok = match keys.get("name") {
         Some(bson_value) => bson_value.as_i32() == Some(1),
@@ -109,20 +119,20 @@ impl MongoDBClient {
         None => false,
       };
 
-      // If both keys match, we found our index
-      if name_ok && timestamp_ok {
+      // If all keys match, we found our index
+      if dataset_ok && name_ok && timestamp_ok {
         already_exists = true;
         break;
       }
     }
-    return already_exists;
+    already_exists
   }
 
   async fn ensure_name_timestamp_indexes(&self, collection_name: &str) -> mongodb::error::Result<()> {
     let collection = self.collection::<Document>(collection_name);
 
     let index_model = IndexModel::builder()
-      .keys(doc! { "name": 1, "timestamp": 1 })
+      .keys(doc! { "dataset": 1, "name": 1, "timestamp": 1 })
       .options(IndexOptions::builder().unique(true).build())
       .build();
 
@@ -132,28 +142,97 @@ impl MongoDBClient {
   }
 }
 
+/// Document Mongo d'un objet céleste, accompagné de sa date en secondes TDB depuis J2000
+/// et du jeu de données dont il est issu
+///
+/// Les dates ISO 8601 à année étendue ne se trient pas dans l'ordre chronologique : les
+/// recherches par intervalle ou par proximité portent sur `seconds`. Les documents sans
+/// jeu de données, antérieurs à son introduction, ne sont plus jamais relus.
+#[derive(Serialize, Deserialize)]
+struct StoredItem {
+  #[serde(flatten)]
+  item: CelestItem,
+  #[serde(default)]
+  seconds: Option<f64>,
+  #[serde(default)]
+  dataset: Option<String>,
+}
+
+impl StoredItem {
+  fn new(item: &CelestItem, dataset: &str) -> Self {
+    StoredItem { item: item.clone(), seconds: item.timestamp.map(Epoch::seconds), dataset: Some(dataset.to_string()) }
+  }
+}
+
+/// Document Mongo des diagnostics d'une simulation, accompagnés de leur jeu de données
+#[derive(Serialize, Deserialize)]
+struct StoredDiagnostics {
+  #[serde(flatten)]
+  diagnostics: Diagnostics,
+  #[serde(default)]
+  dataset: Option<String>,
+}
+
 /// Persister MongoDB pour CelestItem
 pub struct MongoPersistor {
   client: Arc<MongoDBClient>,
   collection_name: String,
+  diagnostics_collection_name: String,
 }
 
 impl MongoPersistor {
   pub async fn new(uri: &str, db_name: &str, collection_name: &str) -> Self {
     let mongo_client = MongoDBClient::new(uri, db_name).await.expect("Failed to create MongoDB client.");
-    MongoPersistor {
+    let persistor = MongoPersistor {
       client: Arc::new(mongo_client),
       collection_name: collection_name.to_string(),
+      diagnostics_collection_name: format!("{}_diagnostics", collection_name),
+    };
+    if let Err(err) = persistor.backfill_seconds().await {
+      tracing::warn!("⚠️ Échec de la migration du champ seconds : {:#}", err);
+    }
+    persistor
+  }
+
+  /// Renseigne `seconds` sur les documents enregistrés avant son introduction, sans quoi ils
+  /// échapperaient aux recherches par intervalle et par proximité
+  async fn backfill_seconds(&self) -> Result<()> {
+    let collection: Collection<Document> = self.client.collection(&self.collection_name);
+
+    let documents: Vec<Document> = collection
+      .find(doc! { "seconds": { "$exists": false } })
+      .await
+      .context("Erreur lors de la requête Mongo")?
+      .try_collect()
+      .await
+      .context("Erreur de lecture des résultats Mongo")?;
+
+    let mut updated = 0;
+    for document in documents {
+      let Some(id) = document.get("_id").cloned() else { continue };
+      // La date est relue par `Epoch`, qui accepte aussi les anciens horodatages
+      let stored: StoredItem = bson::from_document(document).context("Document Mongo illisible")?;
+      let Some(seconds) = stored.item.timestamp.map(Epoch::seconds) else { continue };
+      collection
+        .update_one(doc! { "_id": id }, doc! { "$set": { "seconds": seconds } })
+        .await
+        .context("Échec de la mise à jour Mongo")?;
+      updated += 1;
     }
+
+    if updated > 0 {
+      println!("🌐 Champ seconds ajouté à {} documents Mongo", updated);
+    }
+    Ok(())
   }
 
-  pub async fn save_many(&self, items: &[CelestItem]) -> Result<()> {
-    let collection: Collection<CelestItem> = self.client.collection(&self.collection_name);
+  pub async fn save_many(&self, dataset: &str, items: &[CelestItem]) -> Result<()> {
+    let collection: Collection<StoredItem> = self.client.collection(&self.collection_name);
 
     for item in items {
       // Utilisation de `insert_one`, on peut ajouter upsert plus tard si besoin
       collection
-        .insert_one(item)
+        .insert_one(StoredItem::new(item, dataset))
         .await
         .context("Échec de l'insertion Mongo")?;
     }
@@ -162,11 +241,12 @@ impl MongoPersistor {
     Ok(())
   }
 
-  pub async fn find_by_date(&self, date: DateTime<Utc>) -> Result<Vec<CelestItem>> {
-    let collection: Collection<CelestItem> = self.client.collection(&self.collection_name);
+  pub async fn find_by_date(&self, dataset: &str, date: Epoch) -> Result<Vec<CelestItem>> {
+    let collection: Collection<StoredItem> = self.client.collection(&self.collection_name);
 
     let filter = doc! {
-      "timestamp": date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+      "dataset": dataset,
+      "timestamp": date.to_string()
     };
 
     let cursor = collection
@@ -174,21 +254,22 @@ impl MongoPersistor {
       .await
       .context("Erreur lors de la requête Mongo")?;
 
-    let results: Vec<CelestItem> = cursor
+    let results: Vec<StoredItem> = cursor
       .try_collect()
       .await
       .context("Erreur de lecture des résultats Mongo")?;
 
-    Ok(results)
+    Ok(results.into_iter().map(|stored| stored.item).collect())
   }
 
-  pub async fn find_by_dates(&self, start: DateTime<Utc>, stop: DateTime<Utc>) -> Result<Vec<CelestItem>> {
-    let collection: Collection<CelestItem> = self.client.collection(&self.collection_name);
+  pub async fn find_by_dates(&self, dataset: &str, start: Epoch, stop: Epoch) -> Result<Vec<CelestItem>> {
+    let collection: Collection<StoredItem> = self.client.collection(&self.collection_name);
 
     let filter = doc! {
-      "timestamp": {
-        "$gte": start.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
-        "$lte": stop.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+      "dataset": dataset,
+      "seconds": {
+        "$gte": start.seconds(),
+        "$lte": stop.seconds(),
       }
     };
 
@@ -200,11 +281,67 @@ impl MongoPersistor {
       .await
       .context("Erreur lors de la requête Mongo")?;
 
-    let results: Vec<CelestItem> = cursor
+    let results: Vec<StoredItem> = cursor
       .try_collect()
       .await
       .context("Erreur de lecture des résultats Mongo")?;
 
-    Ok(results)
+    Ok(results.into_iter().map(|stored| stored.item).collect())
+  }
+
+  /// Instantané le plus proche de `date`, avant ou après, vide si la collection l'est aussi
+  pub async fn find_nearest_by_date(&self, dataset: &str, date: Epoch) -> Result<Vec<CelestItem>> {
+    let collection: Collection<StoredItem> = self.client.collection(&self.collection_name);
+    let target = date.seconds();
+
+    let before = collection
+      .find_one(doc! { "dataset": dataset, "seconds": { "$lte": target } })
+      .sort(doc! { "seconds": -1 })
+      .await
+      .context("Erreur lors de la requête Mongo")?;
+    let after = collection
+      .find_one(doc! { "dataset": dataset, "seconds": { "$gte": target } })
+      .sort(doc! { "seconds": 1 })
+      .await
+      .context("Erreur lors de la requête Mongo")?;
+
+    let nearest = [before, after]
+      .into_iter()
+      .flatten()
+      .filter_map(|stored| stored.item.timestamp)
+      .min_by(|a, b| a.seconds_since(date).abs().total_cmp(&b.seconds_since(date).abs()));
+
+    match nearest {
+      Some(timestamp) => self.find_by_date(dataset, timestamp).await,
+      None => Ok(vec![]),
+    }
+  }
+
+  pub async fn save_diagnostics(&self, dataset: &str, diagnostics: &Diagnostics) -> Result<()> {
+    let collection: Collection<StoredDiagnostics> = self.client.collection(&self.diagnostics_collection_name);
+
+    collection
+      .insert_one(StoredDiagnostics { diagnostics: diagnostics.clone(), dataset: Some(dataset.to_string()) })
+      .await
+      .context("Échec de l'insertion Mongo des diagnostics")?;
+
+    println!("🌐 Diagnostics enregistrés dans Mongo");
+    Ok(())
+  }
+
+  pub async fn find_diagnostics_by_date(&self, dataset: &str, date: Epoch) -> Result<Option<Diagnostics>> {
+    let collection: Collection<StoredDiagnostics> = self.client.collection(&self.diagnostics_collection_name);
+
+    let filter = doc! {
+      "dataset": dataset,
+      "timestamp": date.to_string()
+    };
+
+    let result = collection
+      .find_one(filter)
+      .await
+      .context("Erreur lors de la requête Mongo des diagnostics")?;
+
+    Ok(result.map(|stored| stored.diagnostics))
   }
 }
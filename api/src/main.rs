@@ -1,10 +1,9 @@
 use std::env;
 use actix_web::{get, post, web, App, HttpServer, Responder, HttpResponse};
 use actix_cors::Cors;
-use bo::celest_item::CelestItem;
+use bo::simulation_options::SimulationOptions;
 use dal::dao_factory::DAOFactory;
 use serde::Deserialize;
-use chrono::{DateTime, Utc};
 use std::time::Instant;
 use std::sync::Arc;
 
@@ -13,7 +12,18 @@ mod bll;
 mod dal;
 mod utils;
 
+use bll::ephemeris::{self, vsop87::Vsop87, Ephemeris};
+use bll::frames;
+use bll::orbits;
 use bll::simulator::Simulator;
+use bll::sky;
+use bll::time;
+use bo::ephemeris::EphemerisKind;
+use bo::epoch::Epoch;
+use bo::reference_frame::ReferenceFrame;
+use bo::simulation_result::SimulationResult;
+use bo::sky_event::SkySearch;
+use bo::time_scale::TimeScale;
 use utils::logger_factory::LoggerFactory;
 
 #[get("/")]
@@ -21,31 +31,113 @@ async fn home() -> impl Responder {
   HttpResponse::Ok().body("ok")
 }
 
+/// Ajoute au résultat les éléments osculateurs de chaque objet, particules test comprises
+fn with_orbital_elements(result: &mut SimulationResult, central_body: Option<&str>) -> anyhow::Result<()> {
+  let bodies = [result.celest_items.as_slice(), &result.test_particles].concat();
+  result.orbital_elements = orbits::osculating_elements(&bodies, central_body)?;
+  Ok(())
+}
+
+/// Date d'une requête, en ISO 8601 (`date`, année étendue admise), en jour julien (`jd`)
+/// ou en jour julien modifié (`mjd`), lue dans l'échelle de temps `time_scale`
+#[derive(Deserialize)]
+struct DateParams {
+  date: Option<String>,
+  jd: Option<f64>,
+  mjd: Option<f64>,
+  #[serde(default)]
+  time_scale: TimeScale,
+}
+
+impl DateParams {
+  fn resolve(&self) -> anyhow::Result<Epoch> {
+    match (&self.date, self.jd, self.mjd) {
+      (Some(date), None, None) => time::parse(date, self.time_scale),
+      (None, Some(jd), None) => time::from_julian_day(jd, self.time_scale),
+      (None, None, Some(mjd)) => time::from_julian_day(mjd + time::MODIFIED_JULIAN_DAY_OFFSET, self.time_scale),
+      _ => anyhow::bail!("une et une seule des dates date, jd ou mjd est attendue"),
+    }
+  }
+}
+
+/// Bornes d'un intervalle de dates, chacune en ISO 8601 (`from`, `to`), en jour julien
+/// (`from_jd`, `to_jd`) ou en jour julien modifié (`from_mjd`, `to_mjd`), lues comme
+/// `DateParams` dans l'échelle de temps `time_scale`
+#[derive(Deserialize)]
+struct RangeParams {
+  from: Option<String>,
+  from_jd: Option<f64>,
+  from_mjd: Option<f64>,
+  to: Option<String>,
+  to_jd: Option<f64>,
+  to_mjd: Option<f64>,
+  #[serde(default)]
+  time_scale: TimeScale,
+}
+
+impl RangeParams {
+  fn resolve(&self) -> anyhow::Result<(Epoch, Epoch)> {
+    let bound = |date: &Option<String>, jd, mjd| DateParams { date: date.clone(), jd, mjd, time_scale: self.time_scale }.resolve();
+    let from = bound(&self.from, self.from_jd, self.from_mjd).map_err(|e| e.context("from"))?;
+    let to = bound(&self.to, self.to_jd, self.to_mjd).map_err(|e| e.context("to"))?;
+    Ok((from, to))
+  }
+}
+
 #[derive(Deserialize)]
 struct SimulateParams {
-  date: String,
+  #[serde(flatten)]
+  date: DateParams,
+  central_body: Option<String>, // corps de référence des éléments orbitaux, le plus massif par défaut
+  #[serde(default)]
+  ephemeris: EphemerisKind,
+  frame: Option<ReferenceFrame>, // repère des positions renvoyées, barycentrique écliptique par défaut
+  #[serde(flatten)]
+  options: SimulationOptions,
 }
 
 #[post("/simulate")]
 async fn simulate(
   simulator: web::Data<Simulator>,
+  vsop87: web::Data<Vsop87>,
   params: web::Json<SimulateParams>
 ) -> impl Responder {
   let start = Instant::now();
-  tracing::info!("📡 Requête reçue avec date = {}", params.date);
-  tracing::info!("🔭 Nombre de planètes : {}", simulator.celest_items.len());
 
-  let target_date = match DateTime::parse_from_rfc3339(&params.date) {
-    Ok(parsed) => parsed.with_timezone(&Utc),
+  let target_date = match params.date.resolve() {
+    Ok(date) => date,
     Err(e) => {
       return HttpResponse::BadRequest().body(format!("Date invalide : {e}"));
     }
   };
+  tracing::info!("📡 Requête reçue avec date = {}", target_date);
+  tracing::info!("🔭 Nombre de planètes : {}", simulator.celest_items.len());
+
+  if let Err(e) = params.options.validate() {
+    return HttpResponse::BadRequest().body(format!("Options invalides : {e}"));
+  }
 
-  let result = simulator.load_or_compute(target_date).await;
-  let nb_items = result.len();
+  let source: &dyn Ephemeris = match params.ephemeris {
+    EphemerisKind::Numerical => simulator.get_ref(),
+    EphemerisKind::Vsop87 => vsop87.get_ref(),
+  };
+  let mut result = match source.states_at(target_date, &params.options).await {
+    Ok(result) => result,
+    Err(e) => {
+      return HttpResponse::UnprocessableEntity().body(format!("Simulation interrompue : {e}"));
+    }
+  };
+  if let Some(frame) = &params.frame
+    && let Err(e) = frames::to_frame(&mut result, frame)
+  {
+    return HttpResponse::BadRequest().body(format!("Repère invalide : {e}"));
+  }
+  if let Err(e) = with_orbital_elements(&mut result, params.central_body.as_deref()) {
+    return HttpResponse::BadRequest().body(format!("Éléments orbitaux : {e}"));
+  }
+  let nb_items = result.celest_items.len();
   
-  /// convert result to JSON
+  // convert result to JSON
   let result = match serde_json::to_string(&result) {
     Ok(json) => json,
     Err(e) => {
@@ -57,11 +149,71 @@ async fn simulate(
   HttpResponse::Ok().json(result)
 }
 
+#[derive(Deserialize)]
+struct CompareParams {
+  #[serde(flatten)]
+  date: DateParams,
+  #[serde(flatten)]
+  options: SimulationOptions, // options de la solution numérique
+}
+
+/// Écart par corps entre la solution numérique et la théorie VSOP87, en positions héliocentriques
+#[post("/compare_ephemerides")]
+async fn compare_ephemerides(
+  simulator: web::Data<Simulator>,
+  vsop87: web::Data<Vsop87>,
+  params: web::Json<CompareParams>
+) -> impl Responder {
+  let start = Instant::now();
+
+  let target_date = match params.date.resolve() {
+    Ok(date) => date,
+    Err(e) => {
+      return HttpResponse::BadRequest().body(format!("Date invalide : {e}"));
+    }
+  };
+
+  if let Err(e) = params.options.validate() {
+    return HttpResponse::BadRequest().body(format!("Options invalides : {e}"));
+  }
+
+  let (numerical, analytic) = match (
+    simulator.states_at(target_date, &params.options).await,
+    vsop87.states_at(target_date, &params.options).await,
+  ) {
+    (Ok(numerical), Ok(analytic)) => (numerical, analytic),
+    (Err(e), _) | (_, Err(e)) => {
+      return HttpResponse::UnprocessableEntity().body(format!("Simulation interrompue : {e}"));
+    }
+  };
+  let differences = match ephemeris::compare(&numerical.celest_items, &analytic.celest_items) {
+    Ok(differences) => differences,
+    Err(e) => {
+      return HttpResponse::UnprocessableEntity().body(format!("Comparaison impossible : {e}"));
+    }
+  };
+
+  // convert result to JSON
+  let result = match serde_json::to_string(&differences) {
+    Ok(json) => json,
+    Err(e) => {
+      return HttpResponse::InternalServerError().body(format!("Erreur de sérialisation : {e}"));
+    }
+  };
+
+  tracing::info!("📐 Comparaison de {} corps avec VSOP87 en {} ms", differences.len(), start.elapsed().as_millis());
+  HttpResponse::Ok().json(result)
+}
+
 #[derive(Deserialize)]
 struct SimulateRangeParams {
-    from: String,     // DateTime ISO 8601
-    to: String,       // DateTime ISO 8601
-    step_seconds: u64 // Ex: 86400 pour 1 jour
+    #[serde(flatten)]
+    range: RangeParams,
+    step_seconds: u64, // Ex: 86400 pour 1 jour
+    central_body: Option<String>, // corps de référence des éléments orbitaux, le plus massif par défaut
+    frame: Option<ReferenceFrame>, // repère des positions renvoyées, barycentrique écliptique par défaut
+    #[serde(flatten)]
+    options: SimulationOptions,
 }
 
 #[post("/get_simulated_range")]
@@ -71,18 +223,12 @@ async fn get_simulated_range(
 ) -> impl Responder {
   let start = Instant::now();
 
-  let from = match DateTime::parse_from_rfc3339(&params.from) {
-    Ok(parsed) => parsed.with_timezone(&Utc),
-    Err(_) => return HttpResponse::BadRequest().body("Invalid 'from' date"),
+  let (from, to) = match params.range.resolve() {
+    Ok(range) => range,
+    Err(e) => return HttpResponse::BadRequest().body(format!("Invalid date: {e:#}")),
   };
 
   println!("from = {}", from);
-
-  let to = match DateTime::parse_from_rfc3339(&params.to) {
-    Ok(parsed) => parsed.with_timezone(&Utc),
-    Err(_) => return HttpResponse::BadRequest().body("Invalid 'to' date"),
-  };
-
   println!("to = {}", to);
 
   if to <= from || params.step_seconds == 0 {
@@ -90,12 +236,29 @@ async fn get_simulated_range(
     return HttpResponse::BadRequest().body("Invalid range or step");
   }
 
+  if let Err(e) = params.options.validate() {
+    return HttpResponse::BadRequest().body(format!("Invalid options: {e}"));
+  }
+
   println!("step_seconds = {}", params.step_seconds);
 
-  let result = simulator.get_data(from, to).await;
-  let nb_items = result.len();
+  let mut result = match simulator.get_data(from, to, &params.options).await {
+    Ok(result) => result,
+    Err(e) => {
+      return HttpResponse::UnprocessableEntity().body(format!("Simulation interrompue : {e}"));
+    }
+  };
+  if let Some(frame) = &params.frame
+    && let Err(e) = frames::to_frame(&mut result, frame)
+  {
+    return HttpResponse::BadRequest().body(format!("Invalid frame: {e}"));
+  }
+  if let Err(e) = with_orbital_elements(&mut result, params.central_body.as_deref()) {
+    return HttpResponse::BadRequest().body(format!("Invalid central body: {e}"));
+  }
+  let nb_items = result.celest_items.len();
 
-  /// convert result to JSON
+  // convert result to JSON
   let result = match serde_json::to_string(&result) {
     Ok(json) => json,
     Err(e) => {
@@ -107,6 +270,110 @@ async fn get_simulated_range(
   HttpResponse::Ok().json(result)
 }
 
+#[derive(Deserialize)]
+struct EventsParams {
+  #[serde(flatten)]
+  range: RangeParams,
+  #[serde(flatten)]
+  options: SimulationOptions, // `events` précise les événements recherchés, apsides et nœuds par défaut
+}
+
+/// Événements orbitaux de l'intervalle [from, to], dans l'ordre chronologique
+#[post("/find_events")]
+async fn find_events(
+  simulator: web::Data<Simulator>,
+  params: web::Json<EventsParams>
+) -> impl Responder {
+  let start = Instant::now();
+
+  let (from, to) = match params.range.resolve() {
+    Ok(range) => range,
+    Err(e) => {
+      return HttpResponse::BadRequest().body(format!("Date invalide : {e:#}"));
+    }
+  };
+  if to <= from {
+    return HttpResponse::BadRequest().body("Intervalle de dates invalide");
+  }
+
+  let options = SimulationOptions {
+    events: Some(params.options.events.clone().unwrap_or_default()),
+    ..params.options.clone()
+  };
+  if let Err(e) = options.validate() {
+    return HttpResponse::BadRequest().body(format!("Options invalides : {e}"));
+  }
+
+  let events = match simulator.find_events(from, to, &options).await {
+    Ok(events) => events,
+    Err(e) => {
+      return HttpResponse::UnprocessableEntity().body(format!("Simulation interrompue : {e}"));
+    }
+  };
+
+  // convert result to JSON
+  let result = match serde_json::to_string(&events) {
+    Ok(json) => json,
+    Err(e) => {
+      return HttpResponse::InternalServerError().body(format!("Erreur de sérialisation : {e}"));
+    }
+  };
+
+  tracing::info!("🗓️ {} événements orbitaux trouvés en {} ms", events.len(), start.elapsed().as_millis());
+  HttpResponse::Ok().json(result)
+}
+
+#[derive(Deserialize)]
+struct SkyEventsParams {
+  #[serde(flatten)]
+  range: RangeParams,
+  #[serde(flatten)]
+  search: SkySearch, // observateur (la Terre par défaut), seuil des regroupements et échantillonnage
+  #[serde(flatten)]
+  options: SimulationOptions,
+}
+
+/// Conjonctions, oppositions, plus grandes élongations et regroupements vus depuis
+/// l'observateur sur l'intervalle [from, to], dans l'ordre chronologique
+#[post("/find_sky_events")]
+async fn find_sky_events(
+  simulator: web::Data<Simulator>,
+  params: web::Json<SkyEventsParams>
+) -> impl Responder {
+  let start = Instant::now();
+
+  let (from, to) = match params.range.resolve() {
+    Ok(range) => range,
+    Err(e) => {
+      return HttpResponse::BadRequest().body(format!("Date invalide : {e:#}"));
+    }
+  };
+  if to <= from {
+    return HttpResponse::BadRequest().body("Intervalle de dates invalide");
+  }
+  if let Err(e) = params.search.validate(from, to).and_then(|_| params.options.validate()) {
+    return HttpResponse::BadRequest().body(format!("Options invalides : {e}"));
+  }
+
+  let events = match sky::find_sky_events(&simulator, from, to, &params.search, &params.options).await {
+    Ok(events) => events,
+    Err(e) => {
+      return HttpResponse::UnprocessableEntity().body(format!("Recherche interrompue : {e}"));
+    }
+  };
+
+  // convert result to JSON
+  let result = match serde_json::to_string(&events) {
+    Ok(json) => json,
+    Err(e) => {
+      return HttpResponse::InternalServerError().body(format!("Erreur de sérialisation : {e}"));
+    }
+  };
+
+  tracing::info!("🔭 {} événements célestes trouvés en {} ms", events.len(), start.elapsed().as_millis());
+  HttpResponse::Ok().json(result)
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
   // Chargement des variables d'environnement
@@ -121,15 +388,19 @@ async fn main() -> std::io::Result<()> {
   LoggerFactory::init_from_env(log_level, log_output);
 
   // Initialisation DAOFactory + connexion Mongo
-  let daoFactory = Arc::new(DAOFactory::new().await);
+  let dao_factory = Arc::new(DAOFactory::new().await);
   
+  // Conditions initiales : JSON ou export de vecteurs JPL Horizons
   const PLANETS_PATH: &str = "data/celest_items.json";
-  let simulator = web::Data::new(Simulator::new(daoFactory, PLANETS_PATH).await);  
+  let planets_path = env::var("CELEST_ITEMS_PATH").unwrap_or_else(|_| PLANETS_PATH.to_string());
+  let simulator = web::Data::new(Simulator::new(dao_factory, &planets_path).await);
+  let vsop87 = web::Data::new(Vsop87);
 
   println!("🚀 Serveur lancé sur http://{}:{}", address, port);
   HttpServer::new(move || {
     App::new()
       .app_data(simulator.clone())
+      .app_data(vsop87.clone())
       .wrap(
         Cors::default()
             .allowed_origin(&allowerd_origins)
@@ -139,9 +410,40 @@ async fn main() -> std::io::Result<()> {
       )
       .service(home)
       .service(simulate)
+      .service(compare_ephemerides)
       .service(get_simulated_range)
+      .service(find_events)
+      .service(find_sky_events)
   })
   .bind((address, port))?
   .run()
   .await
 }
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn range_bounds_accept_iso_dates_and_julian_days() {
+    let params: EventsParams = serde_json::from_value(serde_json::json!({
+      "from": "2000-01-01T12:00:00Z",
+      "to_mjd": 51_545,
+      "time_scale": "tt",
+      "integrator": "leapfrog",
+    }))
+    .unwrap();
+    let (from, to) = params.range.resolve().unwrap();
+    assert_eq!(from, time::from_julian_day(time::J2000_JULIAN_DAY, TimeScale::Tt).unwrap());
+    assert!((to.seconds_since(from) - 43_200.0).abs() < 1e-3, "{}", to.seconds_since(from));
+    assert_eq!(params.options.integrator, bo::simulation_options::IntegratorKind::Leapfrog);
+
+    // Une borne absente, ou donnée deux fois, est refusée
+    let range = |value| serde_json::from_value::<RangeParams>(value).unwrap().resolve();
+    assert!(range(serde_json::json!({ "from_jd": 2_451_545.0 })).is_err());
+    assert!(range(serde_json::json!({ "from_jd": 2_451_545.0, "to": "2000-01-02T00:00:00Z", "to_jd": 2_451_546.0 })).is_err());
+    assert!(range(serde_json::json!({ "from_jd": 1e300, "to_jd": 2_451_546.0 })).is_err());
+  }
+}
+
@@ -46,7 +46,7 @@ impl Logger for JsonLogger {
 /// Logger désactivé
 pub struct NoopLogger;
 impl Logger for NoopLogger {
-    fn init(&self, level: String) {
+    fn init(&self, _level: String) {
         println!("🟡 Logger désactivé");
     }
 }
@@ -62,7 +62,7 @@ impl Logger for LogstashLogger {
             .with(
                 fmt::layer()
                     .json()
-                    .with_writer(|| LogstashWriter::default())
+                    .with_writer(|| LogstashWriter)
                     .with_current_span(true)
                     .with_span_list(true),
             )
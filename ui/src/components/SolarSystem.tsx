@@ -14,12 +14,20 @@ interface CelestItem {
   position: [number, number, number];
   velocity: [number, number, number];
   timestamp: string;
+  fixed: boolean;
+  test_particle: boolean;
+}
+
+interface SimulationData {
+  celestItems: CelestItem[];
+  testParticles: CelestItem[];
 }
 
 const SolarSystem = () => {
   const [date, setDate] = useState<Date>(new Date('2025-04-11T00:00:00Z'));
   const [passedTime, setPassedTime] = useState<number>(ONE_DAY);
   const [celestItems, setCelestItems] = useState<CelestItem[]>([]);
+  const [testParticles, setTestParticles] = useState<CelestItem[]>([]);
   const [orbitHistory, setOrbitHistory] = useState<Record<string, [number, number, number][]>>({});
   const [started, setStarted] = useState(false);
   const [paused, setPaused] = useState(true);
@@ -36,7 +44,7 @@ const SolarSystem = () => {
 
   const run = async () => {
     const getDataRange = async (start: Date, end: Date) => {
-      const result: CelestItem[] = await getSimulatedRange(
+      const result: SimulationData = await getSimulatedRange(
         start.toISOString(),
         end.toISOString(),
         passedTime / 1000
@@ -52,7 +60,7 @@ const SolarSystem = () => {
         dataProcessing(result);
       } else {
         const newDate = new Date(date.getTime() + passedTime);
-        const result: CelestItem[] = await simulate(newDate.toISOString());
+        const result: SimulationData = await simulate(newDate.toISOString());
         dataProcessing(result);
       }
     } catch (err) {
@@ -60,7 +68,13 @@ const SolarSystem = () => {
     }
   };
 
-  const dataProcessing = async (result: CelestItem[]) => {
+  const dataProcessing = async ({ celestItems: result, testParticles: particles }: SimulationData) => {
+    // Particules test : seule la dernière position de chacune est affichée
+    if (particles && particles.length > 0) {
+      const latest = new Map(particles.map((particle) => [particle.name, particle]));
+      setTestParticles(Array.from(latest.values()));
+    }
+
     if (result && result.length > 0) {
       // Update celest items
       setCelestItems((prev) => {
@@ -138,7 +152,7 @@ const SolarSystem = () => {
         </div>
       </div>
       
-      <SolarSystem3D celestItems={celestItems} orbitHistory={orbitHistory} />
+      <SolarSystem3D celestItems={celestItems} testParticles={testParticles} orbitHistory={orbitHistory} />
     </div>
   );
 };
@@ -14,10 +14,11 @@ interface CelestItem {
 
 interface SolarSystem3DProps {
   celestItems: CelestItem[];
+  testParticles?: CelestItem[];
   orbitHistory?: Record<string, [number, number, number][]>;
 }
 
-function SolarSystemScene({ celestItems, orbitHistory }: SolarSystem3DProps) {
+function SolarSystemScene({ celestItems, testParticles, orbitHistory }: SolarSystem3DProps) {
 
   // Couleur simple par nom
   const colors: Record<string, string> = {
@@ -99,6 +100,22 @@ function SolarSystemScene({ celestItems, orbitHistory }: SolarSystem3DProps) {
         ) : null
       )}
 
+      {/* Particules test (ceintures, essaims de comètes) en un seul nuage de points */}
+      {testParticles && testParticles.length > 0 && (
+        <points key={testParticles.length}>
+          <bufferGeometry>
+            <bufferAttribute
+              attach="attributes-position"
+              args={[
+                new Float32Array(testParticles.flatMap((particle) => particle.position.map((x) => x / POSITION_SCALE))),
+                3,
+              ]}
+            />
+          </bufferGeometry>
+          <pointsMaterial color="lightgray" size={1.5} sizeAttenuation={false} />
+        </points>
+      )}
+
       {/* Points des orbites */}
       {orbitHistory &&
         Object.entries(orbitHistory).flatMap(([name, path]) =>
@@ -121,14 +138,14 @@ function SolarSystemScene({ celestItems, orbitHistory }: SolarSystem3DProps) {
 }
 
 
-export default function SolarSystem3D({ celestItems, orbitHistory }: SolarSystem3DProps) {
+export default function SolarSystem3D({ celestItems, testParticles, orbitHistory }: SolarSystem3DProps) {
   return (
     <div className="w-full h-[600px] rounded-xl overflow-hidden shadow-lg">
       <Canvas
         camera={{ position: [0, 0, 300], near: 0.1, far: 1e7 }}
         style={{ background: 'black' }}
       >
-        <SolarSystemScene celestItems={celestItems} orbitHistory={orbitHistory} />
+        <SolarSystemScene celestItems={celestItems} testParticles={testParticles} orbitHistory={orbitHistory} />
       </Canvas>
     </div>
   );
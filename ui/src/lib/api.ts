@@ -1,5 +1,11 @@
 const API_BASE_URL = 'http://localhost:8080';
 
+// Corps massifs et particules test sont renvoyés dans deux sections distinctes
+function parseResult(body: string) {
+  const result = JSON.parse(body);
+  return { celestItems: result.celest_items, testParticles: result.test_particles ?? [] };
+}
+
 export async function ping(): Promise<string> {
   const response = await fetch(`${API_BASE_URL}/`);
   if (!response.ok) throw new Error('Erreur lors du ping de l\'API');
@@ -16,7 +22,7 @@ export async function simulate(date: string) {
   });
 
   if (!response.ok) throw new Error('Erreur lors de la simulation');
-  return JSON.parse(await response.json());
+  return parseResult(await response.json());
 }
 
 export async function getSimulatedRange(from: string, to: string, step_seconds: number) {
@@ -29,5 +35,5 @@ export async function getSimulatedRange(from: string, to: string, step_seconds:
   });
 
   if (!response.ok) throw new Error('Erreur lors de la simulation');
-  return JSON.parse(await response.json());
+  return parseResult(await response.json());
 }
\ No newline at end of file